@@ -1,6 +1,8 @@
 use std::env;
 
 fn main() {
+    check_vendor_version("vendor/src/meshoptimizer.h");
+
     let mut build = cc::Build::new();
 
     build.include("src");
@@ -13,12 +15,14 @@ fn main() {
         "vendor/src/indexgenerator.cpp",
         "vendor/src/overdrawanalyzer.cpp",
         "vendor/src/overdrawoptimizer.cpp",
+        "vendor/src/quantization.cpp",
         "vendor/src/simplifier.cpp",
         "vendor/src/spatialorder.cpp",
         "vendor/src/stripifier.cpp",
         "vendor/src/vcacheanalyzer.cpp",
         "vendor/src/vcacheoptimizer.cpp",
         "vendor/src/vertexcodec.cpp",
+        "vendor/src/vertexfilter.cpp",
         "vendor/src/vfetchanalyzer.cpp",
         "vendor/src/vfetchoptimizer.cpp",
     ];
@@ -57,6 +61,31 @@ fn main() {
     generate_bindings("gen/bindings.rs");
 }
 
+// The checked in bindings must match the library that gets compiled, since calling a native
+// function through a mismatched declaration is undefined behavior. Regenerating the bindings
+// makes them match whatever version is vendored.
+#[cfg(not(feature = "generate_bindings"))]
+fn check_vendor_version(header: &str) {
+    // Version of the vendored meshoptimizer that `gen/bindings.rs` was generated from.
+    const MESHOPTIMIZER_VERSION: u32 = 210;
+
+    let source = std::fs::read_to_string(header).expect("Unable to read meshoptimizer header!");
+    let version = source.lines().find_map(|line| {
+        line.strip_prefix("#define MESHOPTIMIZER_VERSION")
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|version| version.parse::<u32>().ok())
+    });
+    if let Some(version) = version {
+        assert_eq!(
+            version, MESHOPTIMIZER_VERSION,
+            "vendored meshoptimizer version doesn't match gen/bindings.rs, regenerate the bindings with the generate_bindings feature"
+        );
+    }
+}
+
+#[cfg(feature = "generate_bindings")]
+fn check_vendor_version(_: &str) {}
+
 #[cfg(feature = "generate_bindings")]
 fn generate_bindings(output_file: &str) {
     let bindings = bindgen::Builder::default()
@@ -66,6 +95,7 @@ fn generate_bindings(output_file: &str) {
         .impl_debug(true)
         .blocklist_type("__darwin_.*")
         .allowlist_function("meshopt.*")
+        .allowlist_var("meshopt.*")
         .trust_clang_mangling(false)
         .layout_tests(false)
         .size_t_is_usize(true)
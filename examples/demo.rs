@@ -4,6 +4,7 @@ use memoffset::offset_of;
 use meshopt::*;
 use rand::{seq::SliceRandom, thread_rng};
 use std::{
+    collections::{HashMap, HashSet},
     fmt,
     fs::File,
     io::Write,
@@ -236,6 +237,45 @@ impl Mesh {
         Ok(())
     }
 
+    fn create_cube() -> Self {
+        let mut mesh = Self {
+            vertices: Vec::with_capacity(24),
+            indices: Vec::with_capacity(36),
+        };
+
+        for axis in 0..3 {
+            for sign in [-1f32, 1f32] {
+                let mut n = [0f32; 3];
+                n[axis] = sign;
+                let mut u = [0f32; 3];
+                u[(axis + 1) % 3] = sign;
+                let mut v = [0f32; 3];
+                v[(axis + 2) % 3] = 1f32;
+
+                // corners in counter-clockwise order around the outward normal (u x v = n)
+                let base = mesh.vertices.len() as u32;
+                for (su, sv) in [(-1f32, -1f32), (1f32, -1f32), (1f32, 1f32), (-1f32, 1f32)] {
+                    let p = [0, 1, 2].map(|k| n[k] + su * u[k] + sv * v[k]);
+                    mesh.vertices.push(Vertex {
+                        p,
+                        n,
+                        t: [(su + 1f32) / 2f32, (sv + 1f32) / 2f32],
+                    });
+                }
+                mesh.indices.extend_from_slice(&[
+                    base,
+                    base + 1,
+                    base + 2,
+                    base,
+                    base + 2,
+                    base + 3,
+                ]);
+            }
+        }
+
+        mesh
+    }
+
     fn create_plane(size: u32) -> Self {
         let mut mesh = Self {
             vertices: Vec::with_capacity((size as usize + 1) * (size as usize + 1)),
@@ -409,6 +449,21 @@ fn opt_fetch_remap(mesh: &mut Mesh) {
     mesh.vertices = meshopt::remap_vertex_buffer(&mesh.vertices, mesh.vertices.len(), &remap);
 }
 
+fn opt_spatial_sort(mesh: &mut Mesh) {
+    let remap = meshopt::spatial_sort_remap(&mesh.vertex_adapter());
+    mesh.indices = meshopt::remap_index_buffer(Some(&mesh.indices), mesh.indices.len(), &remap);
+    mesh.vertices = meshopt::remap_vertex_buffer(&mesh.vertices, mesh.vertices.len(), &remap);
+}
+
+fn opt_spatial_sort_triangles(mesh: &mut Mesh) {
+    mesh.indices = meshopt::spatial_sort_triangles(&mesh.indices, &mesh.vertex_adapter());
+}
+
+fn opt_spatial_sort_triangles_cache(mesh: &mut Mesh) {
+    opt_spatial_sort_triangles(mesh);
+    opt_cache(mesh);
+}
+
 fn opt_complete(mesh: &mut Mesh) {
     {
         let (vertex_adapter, indices) = mesh.split();
@@ -426,7 +481,7 @@ fn opt_complete(mesh: &mut Mesh) {
     mesh.vertices.resize(final_size, Default::default());
 }
 
-fn stripify(mesh: &Mesh, use_restart: bool) {
+fn stripify(mesh: &Mesh, use_restart: bool, desc: &str) -> usize {
     let restart_index = if use_restart { 0xffffffff } else { 0x00000000 };
 
     let process_start = Instant::now();
@@ -438,6 +493,7 @@ fn stripify(mesh: &Mesh, use_restart: bool) {
 
     assert!(copy.is_valid());
     assert_eq!(mesh, &copy);
+    assert_eq!(copy.indices.len(), mesh.indices.len());
 
     let vcs =
         meshopt::analyze_vertex_cache(&copy.indices, copy.vertices.len(), CACHE_SIZE as u32, 0, 0);
@@ -446,7 +502,7 @@ fn stripify(mesh: &Mesh, use_restart: bool) {
     let vcs_intel = meshopt::analyze_vertex_cache(&copy.indices, copy.vertices.len(), 128, 0, 0);
 
     println!("Stripify{}: ACMR {:.6} ATVR {:.6} (NV {:.6} AMD {:.6} Intel {:.6}); {} strip indices ({:.1}%) in {:.2} msec",
-        if use_restart { "R" } else { " " },
+        desc,
         vcs.acmr,
         vcs.atvr,
         vcs_nv.atvr,
@@ -456,6 +512,66 @@ fn stripify(mesh: &Mesh, use_restart: bool) {
         strip.len() as f64 / mesh.indices.len() as f64 * 100f64,
         elapsed_to_ms(process_elapsed),
     );
+
+    strip.len()
+}
+
+fn cull_known_answers() {
+    // normal cone pointing along +Z with a 60 degree half-angle, bounding sphere at the origin
+    let bounds = meshopt::Bounds {
+        center: [0.0, 0.0, 0.0],
+        radius: 1.0,
+        cone_apex: [0.0, 0.0, 0.0],
+        cone_axis: [0.0, 0.0, 1.0],
+        cone_cutoff: 0.5,
+        cone_axis_s8: [0, 0, 127],
+        cone_cutoff_s8: 64,
+    };
+
+    // perspective: a camera behind the cone sees the back faces, one in front of it doesn't
+    assert!(bounds.is_backfacing([0.0, 0.0, -10.0]));
+    assert!(!bounds.is_backfacing([0.0, 0.0, 10.0]));
+    assert!(bounds.is_backfacing_sphere([0.0, 0.0, -10.0]));
+    assert!(!bounds.is_backfacing_sphere([0.0, 0.0, 10.0]));
+
+    // orthographic: looking along the cone axis sees the back faces, looking against it doesn't
+    assert!(bounds.is_backfacing_ortho([0.0, 0.0, 1.0]));
+    assert!(!bounds.is_backfacing_ortho([0.0, 0.0, -1.0]));
+
+    let frustum = [
+        meshopt::Plane::new([1.0, 0.0, 0.0], 1.0),
+        meshopt::Plane::new([-1.0, 0.0, 0.0], 1.0),
+        meshopt::Plane::new([0.0, 1.0, 0.0], 1.0),
+        meshopt::Plane::new([0.0, -1.0, 0.0], 1.0),
+        meshopt::Plane::new([0.0, 0.0, 1.0], 1.0),
+        meshopt::Plane::new([0.0, 0.0, -1.0], 1.0),
+    ];
+    let sphere = |center: [f32; 3], radius: f32| meshopt::Bounds {
+        center,
+        radius,
+        ..bounds
+    };
+
+    // center exactly on the x = 1 plane
+    assert!(sphere([1.0, 0.0, 0.0], 0.5).intersects_frustum(&frustum));
+    // outside of the x = 1 plane, touching it
+    assert!(sphere([2.0, 0.0, 0.0], 1.0).intersects_frustum(&frustum));
+    // fully outside of the x = 1 plane
+    assert!(!sphere([3.0, 0.0, 0.0], 1.0).intersects_frustum(&frustum));
+
+    // sphere on the view axis at distance 5 with radius 3: the tangents from the eye have a
+    // slope of 3 / 4, so the clip space extent is [-0.75 * p, 0.75 * p] on both axes
+    let rect = sphere([0.0, 0.0, 0.0], 3.0)
+        .project_sphere([0.0, 0.0, 5.0], 0.1, 1.0, 0.5)
+        .unwrap();
+    let expected = [0.125, 0.3125, 0.875, 0.6875];
+    for (value, expected) in rect.iter().zip(expected) {
+        assert!((value - expected).abs() < 1e-6);
+    }
+    // a sphere crossing the near plane can't be projected
+    assert!(sphere([0.0, 0.0, 0.0], 3.0)
+        .project_sphere([0.0, 0.0, 3.0], 0.1, 1.0, 0.5)
+        .is_none());
 }
 
 fn shadow(mesh: &Mesh) {
@@ -495,22 +611,365 @@ fn shadow(mesh: &Mesh) {
     );
 }
 
-fn meshlets(mesh: &Mesh) {
+/// Checks adjacency patches against the mesh topology (welded by position) and returns the
+/// number of open edges and the number of edges without a neighbor triangle.
+fn check_adjacency(mesh: &Mesh, adjacency_indices: &[u32]) -> (usize, usize) {
+    let position = |index: u32| mesh.vertices[index as usize].p.map(f32::to_bits);
+
+    // directed edge => positions of the opposite vertices of all triangles using it
+    let mut opposite: HashMap<_, Vec<_>> = HashMap::new();
+    for triangle in mesh.indices.chunks_exact(3) {
+        for edge in 0..3 {
+            let a = position(triangle[edge]);
+            let b = position(triangle[(edge + 1) % 3]);
+            let c = position(triangle[(edge + 2) % 3]);
+            opposite.entry((a, b)).or_default().push(c);
+        }
+    }
+
+    let mut open_edges = 0usize;
+    let mut boundary_edges = 0usize;
+    for (triangle, patch) in mesh
+        .indices
+        .chunks_exact(3)
+        .zip(adjacency_indices.chunks_exact(6))
+    {
+        assert_eq!(triangle, [patch[0], patch[2], patch[4]]);
+        for edge in 0..3 {
+            let a = position(triangle[edge]);
+            let b = position(triangle[(edge + 1) % 3]);
+            let neighbors = opposite.get(&(b, a));
+            if neighbors.is_none() {
+                boundary_edges += 1;
+            }
+
+            let vertex = patch[edge * 2 + 1];
+            assert!((vertex as usize) < mesh.vertices.len());
+            if vertex == triangle[(edge + 2) % 3] {
+                // edges without a neighbor (open edges) use the opposite vertex of the triangle itself
+                open_edges += 1;
+            } else {
+                // otherwise the vertex is the opposite vertex of a triangle sharing the reversed edge
+                assert!(neighbors.is_some_and(|n| n.contains(&position(vertex))));
+            }
+        }
+    }
+
+    (open_edges, boundary_edges)
+}
+
+fn adjacency(mesh: &Mesh) {
+    let vertex_adapter = mesh.vertex_adapter();
+
+    let process_start = Instant::now();
+    let adjacency_indices = meshopt::generate_adjacency_indices(&mesh.indices, &vertex_adapter);
+    let process_elapsed = process_start.elapsed();
+
+    assert_eq!(adjacency_indices.len(), mesh.indices.len() * 2);
+
+    let (open_edges, _) = check_adjacency(mesh, &adjacency_indices);
+
+    println!(
+        "Adjacency: {} patches, {} open edges in {:.2} msec",
+        adjacency_indices.len() / 6,
+        open_edges,
+        elapsed_to_ms(process_elapsed)
+    );
+}
+
+fn adjacency_closed_open() {
+    // closed mesh: faces have their own vertices, so neighbors are only found by position
+    let cube = Mesh::create_cube();
+    let adjacency_indices =
+        meshopt::generate_adjacency_indices_decoder(&cube.indices, &cube.vertices);
+    assert_eq!(check_adjacency(&cube, &adjacency_indices), (0, 0));
+
+    // open mesh: every boundary edge falls back to the triangle's own vertex, and only those do
+    let size = 8;
+    let plane = Mesh::create_plane(size);
+    let adjacency_indices =
+        meshopt::generate_adjacency_indices_decoder(&plane.indices, &plane.vertices);
+    let (open_edges, boundary_edges) = check_adjacency(&plane, &adjacency_indices);
+    assert_eq!(boundary_edges, 4 * size as usize);
+    assert_eq!(open_edges, boundary_edges);
+}
+
+fn indices_16_boundary() {
+    // 65535 is the largest index that fits, 65536 used to wrap around to 0
+    let indices = meshopt::convert_indices_32_to_16(&[0, 1, 65535]).unwrap();
+    assert_eq!(indices, [0, 1, 65535]);
+    assert_eq!(meshopt::convert_indices_16_to_32(&indices), [0, 1, 65535]);
+    let error = meshopt::convert_indices_32_to_16(&[0, 1, 65535, 65536]).unwrap_err();
+    assert!(error.to_string().contains("(65536) at position 3"));
+
+    // the restart value maps between both sizes, and 0xffff can't be used as a regular index
+    let strip = [0, 1, 2, u32::MAX, 3, 4, 65534];
+    let indices = meshopt::convert_indices_32_to_16_with_restart(&strip).unwrap();
+    assert_eq!(indices, [0, 1, 2, 0xffff, 3, 4, 65534]);
+    assert_eq!(
+        meshopt::convert_indices_16_to_32_with_restart(&indices),
+        strip
+    );
+    let reserved = [0, 1, 0xffff];
+    let error = meshopt::convert_indices_32_to_16_with_restart(&reserved).unwrap_err();
+    assert!(error.to_string().contains("(65535) at position 2"));
+    assert!(meshopt::can_convert_indices_32_to_16(&[0, 1, 65535]));
+    assert!(!meshopt::can_convert_indices_32_to_16(&[0, 1, 65536]));
+    assert!(meshopt::can_convert_indices_32_to_16_with_restart(&strip));
+    assert!(!meshopt::can_convert_indices_32_to_16_with_restart(
+        &reserved
+    ));
+}
+
+fn indices_16(mesh: &Mesh) {
+    // remapped indices up to 0xffff fit in 16 bits
+    let remap = (0..=u32::from(u16::MAX)).collect::<Vec<u32>>();
+    let indices: Vec<u16> = meshopt::remap_index_buffer(None, remap.len(), &remap);
+    assert_eq!(indices.last(), Some(&u16::MAX));
+
+    if mesh.vertices.len() > usize::from(u16::MAX) {
+        return;
+    }
+    let vertex_count = mesh.vertices.len();
+    let indices = meshopt::convert_indices_32_to_16(&mesh.indices).unwrap();
+    assert_eq!(meshopt::convert_indices_16_to_32(&indices), mesh.indices);
+    assert_eq!(
+        meshopt::generate_vertex_remap_indexed(&mesh.vertices, &indices),
+        meshopt::generate_vertex_remap(&mesh.vertices, Some(&mesh.indices))
+    );
+
+    let start = Instant::now();
+    let mut optimized = meshopt::optimize_vertex_cache(&indices, vertex_count);
+    meshopt::optimize_overdraw_in_place_decoder(&mut optimized, &mesh.vertices, 1.05);
+    let vertices = meshopt::optimize_vertex_fetch(&mut optimized, &mesh.vertices);
+    let strip = meshopt::stripify(&optimized, vertices.len(), 0xffff).unwrap();
+    let elapsed = start.elapsed();
+
+    // 16-bit indices go through the same native code, so the results match the 32-bit path
+    let mut expected = meshopt::optimize_vertex_cache(&mesh.indices, vertex_count);
+    meshopt::optimize_overdraw_in_place_decoder(&mut expected, &mesh.vertices, 1.05);
+    meshopt::optimize_vertex_fetch(&mut expected, &mesh.vertices);
+    assert!(optimized
+        .iter()
+        .zip(&expected)
+        .all(|(&index, &expected)| u32::from(index) == expected));
+    let triangles = meshopt::unstripify(&strip, 0xffff).unwrap();
+    assert_eq!(triangles.len(), optimized.len());
+
+    // meshes that don't fit in 16 bits can still be drawn with 16-bit indices as several draws
+    assert!(meshopt::can_convert_indices_32_to_16(&mesh.indices));
+    let offset = u32::from(u16::MAX);
+    let shifted = mesh
+        .indices
+        .iter()
+        .chain(&mesh.indices)
+        .enumerate()
+        .map(|(i, &index)| {
+            if i < mesh.indices.len() {
+                index
+            } else {
+                index + offset
+            }
+        })
+        .collect::<Vec<u32>>();
+    assert!(meshopt::convert_indices_32_to_16(&shifted).is_err());
+    let (split, draws) = meshopt::split_indices_32_to_16(&shifted).unwrap();
+    assert!(draws.len() >= 2);
+    for draw in &draws {
+        for i in draw.index_range.clone() {
+            assert_eq!(u32::from(split[i]) + draw.base_vertex, shifted[i]);
+        }
+    }
+
+    let vcs = meshopt::analyze_vertex_cache(&optimized, vertices.len(), CACHE_SIZE as u32, 0, 0);
+    println!(
+        "{:9}: ACMR {:.6} ATVR {:.6}, {} strip indices in {:.2} msec",
+        "Index16",
+        vcs.acmr,
+        vcs.atvr,
+        strip.len(),
+        elapsed_to_ms(elapsed),
+    );
+}
+
+/// Checks tessellation patches against the mesh topology (welded by position) and returns the
+/// number of open edges.
+fn check_tessellation(mesh: &Mesh, tessellation_indices: &[u32]) -> usize {
+    let position = |index: u32| mesh.vertices[index as usize].p.map(f32::to_bits);
+
+    // the dominant vertex of a position is the first vertex that has it
+    let mut dominant: HashMap<_, u32> = HashMap::new();
+    for index in 0..mesh.vertices.len() as u32 {
+        dominant.entry(position(index)).or_insert(index);
+    }
+
+    let mut edges: HashSet<(u32, u32)> = HashSet::new();
+    let mut edge_positions = HashSet::new();
+    for triangle in mesh.indices.chunks_exact(3) {
+        for edge in 0..3 {
+            let a = triangle[edge];
+            let b = triangle[(edge + 1) % 3];
+            edges.insert((a, b));
+            edge_positions.insert((position(a), position(b)));
+        }
+    }
+
+    let mut open_edges = 0usize;
+    for (triangle, patch) in mesh
+        .indices
+        .chunks_exact(3)
+        .zip(tessellation_indices.chunks_exact(12))
+    {
+        assert_eq!(triangle, &patch[0..3]);
+        for edge in 0..3 {
+            let a = triangle[edge];
+            let b = triangle[(edge + 1) % 3];
+            let (c, d) = (patch[3 + edge * 2], patch[4 + edge * 2]);
+            if edge_positions.contains(&(position(b), position(a))) {
+                // the opposing edge is the same edge of the neighbor triangle, which may use
+                // different vertices at the same positions
+                assert!(edges.contains(&(d, c)));
+                assert_eq!((position(c), position(d)), (position(a), position(b)));
+            } else {
+                // open edges use the edge of the triangle itself
+                assert_eq!((c, d), (a, b));
+                open_edges += 1;
+            }
+            assert_eq!(patch[9 + edge], dominant[&position(a)]);
+        }
+    }
+
+    open_edges
+}
+
+fn tessellation(mesh: &Mesh) {
+    let vertex_adapter = mesh.vertex_adapter();
+
+    let process_start = Instant::now();
+    let tessellation_indices =
+        meshopt::generate_tessellation_indices(&mesh.indices, &vertex_adapter);
+    let process_elapsed = process_start.elapsed();
+
+    assert_eq!(tessellation_indices.len(), mesh.indices.len() * 4);
+
+    let open_edges = check_tessellation(mesh, &tessellation_indices);
+
+    println!(
+        "Tessellat: {} patches, {} open edges in {:.2} msec",
+        tessellation_indices.len() / 12,
+        open_edges,
+        elapsed_to_ms(process_elapsed)
+    );
+}
+
+fn tessellation_layout() {
+    // two triangles sharing the edge between (1, 0) and (0, 1), which has separate vertices in
+    // each triangle (1 and 4, 2 and 5)
+    let p = |x: f32, y: f32| Vertex {
+        p: [x, y, 0.0],
+        ..Vertex::default()
+    };
+    let mesh = Mesh {
+        vertices: vec![
+            p(0.0, 0.0),
+            p(1.0, 0.0),
+            p(0.0, 1.0),
+            p(1.0, 1.0),
+            p(1.0, 0.0),
+            p(0.0, 1.0),
+        ],
+        indices: vec![0, 1, 2, 5, 4, 3],
+    };
+    let tessellation_indices =
+        meshopt::generate_tessellation_indices_decoder(&mesh.indices, &mesh.vertices);
+    #[rustfmt::skip]
+    let expected = [
+        // triangle, opposing edges, dominant vertices
+        0, 1, 2, 0, 1, 4, 5, 2, 0, 0, 1, 2,
+        5, 4, 3, 2, 1, 4, 3, 3, 5, 2, 1, 3,
+    ];
+    assert_eq!(tessellation_indices, expected);
+    assert_eq!(check_tessellation(&mesh, &tessellation_indices), 4);
+
+    // closed mesh with split faces: the 4 outer edges of each face take their opposing edge from
+    // the vertices of the adjacent face, while the diagonal is shared within the face
+    let cube = Mesh::create_cube();
+    let tessellation_indices =
+        meshopt::generate_tessellation_indices_decoder(&cube.indices, &cube.vertices);
+    assert_eq!(check_tessellation(&cube, &tessellation_indices), 0);
+    let mut split_edges = 0;
+    for (triangle, patch) in cube
+        .indices
+        .chunks_exact(3)
+        .zip(tessellation_indices.chunks_exact(12))
+    {
+        for edge in 0..3 {
+            if !triangle.contains(&patch[3 + edge * 2]) {
+                split_edges += 1;
+            }
+        }
+    }
+    assert_eq!(split_edges, 6 * 4);
+}
+
+fn meshlets(mesh: &Mesh, scan: bool) {
     let max_vertices = 64;
     let max_triangles = 124;
 
     let vertex_adapter = mesh.vertex_adapter();
 
     let process_start = Instant::now();
-    let meshlets = meshopt::build_meshlets(
-        &mesh.indices,
-        &vertex_adapter,
-        max_vertices,
-        max_triangles,
-        0.5, // cone weight
-    );
+    let mut meshlets = if scan {
+        // scan-based meshlet builder doesn't need vertex positions
+        meshopt::build_meshlets_scan(
+            &mesh.indices,
+            mesh.vertices.len(),
+            max_vertices,
+            max_triangles,
+        )
+        .unwrap()
+    } else {
+        meshopt::build_meshlets(
+            &mesh.indices,
+            &vertex_adapter,
+            max_vertices,
+            max_triangles,
+            0.5, // cone weight
+        )
+        .unwrap()
+    };
     let process_elapsed = process_start.elapsed();
 
+    meshlets.trim();
+
+    let packed = meshlets.pack(meshopt::MeshletTriangleFormat::Bytes);
+    let packed_u32 = meshlets.pack(meshopt::MeshletTriangleFormat::PackedU32);
+    let triangle_count: usize = meshlets
+        .meshlets
+        .iter()
+        .map(|meshlet| meshlet.triangle_count as usize)
+        .sum();
+    assert_eq!(packed.descriptor_bytes().len(), meshlets.len() * 16);
+    assert_eq!(packed.vertex_bytes().len(), meshlets.vertices().len() * 4);
+    assert_eq!(packed_u32.triangles.len(), triangle_count);
+    for (i, meshlet) in meshlets.iter().enumerate() {
+        let descriptor = &packed.descriptors[i];
+        let start = descriptor.triangle_offset as usize;
+        assert_eq!(
+            &packed.triangle_bytes()[start..start + meshlet.triangles.len()],
+            meshlet.triangles
+        );
+        let descriptor = &packed_u32.descriptors[i];
+        let start = descriptor.triangle_offset as usize;
+        for (j, triangle) in meshlet.triangles.chunks_exact(3).enumerate() {
+            let word = packed_u32.triangles[start + j];
+            assert_eq!(
+                [word as u8, (word >> 8) as u8, (word >> 16) as u8],
+                [triangle[0], triangle[1], triangle[2]]
+            );
+        }
+    }
+
     let mut avg_vertices = 0f64;
     let mut avg_triangles = 0f64;
     let mut not_full = 0usize;
@@ -528,13 +987,103 @@ fn meshlets(mesh: &Mesh) {
     avg_vertices /= meshlets.len() as f64;
     avg_triangles /= meshlets.len() as f64;
 
-    println!("Meshlets : {} meshlets (avg vertices {:.1}, avg triangles {:.1}, not full {}) in {:.2} msec",
+    println!("Meshlets{}: {} meshlets (avg vertices {:.1}, avg triangles {:.1}, not full {}) in {:.2} msec",
+        if scan { "S" } else { " " },
         meshlets.len(),
         avg_vertices,
         avg_triangles,
         not_full,
         elapsed_to_ms(process_elapsed));
 
+    let adjacency = meshlets.adjacency(&vertex_adapter);
+    assert_eq!(adjacency.len(), meshlets.len());
+    for meshlet in 0..adjacency.len() {
+        for neighbor in adjacency.neighbors(meshlet) {
+            assert!(adjacency
+                .neighbors(neighbor.meshlet)
+                .iter()
+                .any(
+                    |other| other.meshlet == meshlet && other.shared_edges == neighbor.shared_edges
+                ));
+        }
+    }
+
+    let groups = meshlets.partition(&vertex_adapter, 4).unwrap();
+    let mut grouped = groups.iter().flatten().copied().collect::<Vec<usize>>();
+    grouped.sort_unstable();
+    assert!(grouped.into_iter().eq(0..meshlets.len()));
+
+    println!(
+        "{:9}: {} groups (avg meshlets {:.1}, max {})",
+        "MeshletGrp",
+        groups.len(),
+        meshlets.len() as f64 / groups.len() as f64,
+        groups.iter().map(|group| group.len()).max().unwrap_or(0),
+    );
+
+    let stats = meshopt::analyze_meshlets(&meshlets, &vertex_adapter, max_vertices, max_triangles);
+    assert_eq!(stats.meshlet_count as usize, meshlets.len());
+    assert!(stats.vertices_max as usize <= max_vertices);
+    assert!(stats.triangles_max as usize <= max_triangles);
+    assert_eq!(
+        stats.cone_cutoff_histogram.iter().sum::<u32>(),
+        stats.cone_cullable
+    );
+
+    println!(
+        "{:9}: vertex fill {:.1}% ({}..{}), triangle fill {:.1}% ({}..{}), {} cone cullable, sphere tightness {:.2}, {:.2} bytes/triangle",
+        "MeshletSt",
+        stats.vertex_utilization * 100.0,
+        stats.vertices_min,
+        stats.vertices_max,
+        stats.triangle_utilization * 100.0,
+        stats.triangles_min,
+        stats.triangles_max,
+        stats.cone_cullable,
+        stats.sphere_tightness,
+        stats.index_bytes_per_triangle,
+    );
+
+    let encode_start = Instant::now();
+    let encoded = meshopt::encode_meshlet_triangles(&meshlets);
+    let encode_elapsed = encode_start.elapsed();
+
+    let decode_start = Instant::now();
+    let decoded = encoded.decode().unwrap();
+    let decode_elapsed = decode_start.elapsed();
+
+    // triangles are reordered and rotated, but must reference the same vertices in the same winding
+    assert_eq!(decoded.len(), meshlets.len());
+    for (meshlet, decoded) in meshlets.iter().zip(decoded.iter()) {
+        let canonical = |meshlet: meshopt::Meshlet<'_>| {
+            let mut triangles = meshlet
+                .triangles
+                .chunks_exact(3)
+                .map(|triangle| {
+                    let mut triangle = [
+                        meshlet.vertices[triangle[0] as usize],
+                        meshlet.vertices[triangle[1] as usize],
+                        meshlet.vertices[triangle[2] as usize],
+                    ];
+                    let first = (0..3).min_by_key(|&i| triangle[i]).unwrap();
+                    triangle.rotate_left(first);
+                    triangle
+                })
+                .collect::<Vec<[u32; 3]>>();
+            triangles.sort_unstable();
+            triangles
+        };
+        assert_eq!(canonical(meshlet), canonical(decoded));
+    }
+
+    println!(
+        "{:9}: {:.2} bits/triangle; encode {:.2} msec, decode {:.2} msec",
+        "MeshletEnc",
+        (encoded.data.len() * 8) as f64 / f64::from(stats.triangle_count),
+        elapsed_to_ms(encode_elapsed),
+        elapsed_to_ms(decode_elapsed),
+    );
+
     let camera: [f32; 3] = [100.0, 100.0, 100.0];
 
     let mut rejected = 0;
@@ -544,10 +1093,17 @@ fn meshlets(mesh: &Mesh) {
     let mut accepted = 0;
     let mut accepted_s8 = 0;
 
-    let test_start = Instant::now();
-    for meshlet in meshlets.iter() {
-        let bounds = meshopt::compute_meshlet_bounds(meshlet, &vertex_adapter);
+    let cull_data = meshlets.compute_bounds(&vertex_adapter);
+    assert_eq!(cull_data.len(), meshlets.len());
+    if let Some(first) = cull_data.first() {
+        assert_eq!(
+            *first,
+            meshopt::compute_meshlet_bounds(meshlets.get(0), &vertex_adapter).into()
+        );
+    }
 
+    let test_start = Instant::now();
+    for bounds in &cull_data {
         // trivial accept: we can't ever backface cull this meshlet
         if bounds.cone_cutoff >= 1f32 {
             accepted += 1;
@@ -610,6 +1166,34 @@ fn meshlets(mesh: &Mesh) {
     }
     let test_elapsed = test_start.elapsed();
 
+    // the cull helpers on Bounds must agree with the formulas above
+    let mut rejected_bounds = 0;
+    let mut rejected_alt_bounds = 0;
+    let mut visible = 0;
+    let frustum = [
+        meshopt::Plane::new([1.0, 0.0, 0.0], 1.0),
+        meshopt::Plane::new([-1.0, 0.0, 0.0], 1.0),
+        meshopt::Plane::new([0.0, 1.0, 0.0], 1.0),
+        meshopt::Plane::new([0.0, -1.0, 0.0], 1.0),
+        meshopt::Plane::new([0.0, 0.0, 1.0], 1.0),
+        meshopt::Plane::new([0.0, 0.0, -1.0], 1.0),
+    ];
+    for meshlet in meshlets.iter() {
+        let bounds = meshopt::compute_meshlet_bounds(meshlet, &vertex_adapter);
+        rejected_bounds += bounds.is_backfacing(camera) as usize;
+        rejected_alt_bounds += bounds.is_backfacing_sphere(camera) as usize;
+        if bounds.intersects_frustum(&frustum) {
+            visible += 1;
+            let center_view = [bounds.center[0], bounds.center[1], bounds.center[2] + 10.0];
+            if let Some(rect) = bounds.project_sphere(center_view, 0.1, 1.0, 1.0) {
+                assert!(rect[0] <= rect[2] && rect[1] <= rect[3]);
+            }
+        }
+    }
+    assert_eq!(rejected_bounds, rejected);
+    assert_eq!(rejected_alt_bounds, rejected_alt);
+    assert!(visible > 0);
+
     println!("ConeCull : rejected apex {} ({:.1}%) / center {} ({:.1}%), trivially accepted {} ({:.1}%) in {:.2} msec",
            rejected,
            rejected as f64 / (meshlets.len() as f64) * 100.0,
@@ -641,6 +1225,10 @@ fn simplify(mesh: &Mesh) {
     let mut lods: Vec<Vec<u32>> = Vec::with_capacity(lod_count);
     lods.push(mesh.indices.clone());
 
+    // since each LOD is simplified from the previous one, the error of the last LOD is (conservatively)
+    // the sum of the errors of all levels
+    let mut lod_error = 0f32;
+
     for i in 1..lod_count {
         let threshold = 0.7f32.powf(i as f32);
         let target_index_count = (mesh.indices.len() as f32 * threshold) as usize / 3 * 3;
@@ -650,12 +1238,14 @@ fn simplify(mesh: &Mesh) {
             // we can simplify all the way from base level or from the last result
             // simplifying from the base level sometimes produces better results, but simplifying from last level is faster
             let src = &lods[lods.len() - 1];
-            lod = meshopt::simplify(
+            let (result, result_error) = meshopt::simplify_with_error(
                 src,
                 &vertex_adapter,
                 ::std::cmp::min(src.len(), target_index_count),
                 target_error,
             );
+            lod = result;
+            lod_error += result_error;
         }
         lods.push(lod);
     }
@@ -706,11 +1296,13 @@ fn simplify(mesh: &Mesh) {
     let optimize_elapsed = optimize_start.elapsed();
 
     println!(
-        "{:9}: {} triangles => {} LOD levels down to {} triangles in {:.2} msec, optimized in {:.2} msec",
+        "{:9}: {} triangles => {} LOD levels down to {} triangles (error {:.2e}, absolute {:.2e}) in {:.2} msec, optimized in {:.2} msec",
         "Simplify",
         lod_counts[0] / 3,
         lod_count,
         lod_counts[lod_count - 1] / 3,
+        lod_error,
+        lod_error * meshopt::simplify_scale(&vertex_adapter),
         elapsed_to_ms(process_elapsed),
         elapsed_to_ms(optimize_elapsed),
     );
@@ -761,11 +1353,362 @@ fn simplify(mesh: &Mesh) {
     );
 }
 
-fn encode_index(mesh: &Mesh) {
+fn simplify_lod_chain(mesh: &Mesh) {
+    let vertex_adapter = mesh.vertex_adapter();
+
+    // same LOD targets as `simplify`, but with each LOD simplified from the base level
+    let process_start = Instant::now();
+    let lods = LodChain::new()
+        .ratios(&[0.7, 0.49, 0.343, 0.2401], 1e-2)
+        .min_triangle_count(16)
+        .build(&mesh.indices, &vertex_adapter)
+        .unwrap();
+    let process_elapsed = process_start.elapsed();
+
+    assert_eq!(lods.vertex_stride, mem::size_of::<Vertex>());
+    assert!(lods
+        .indices
+        .iter()
+        .all(|&index| (index as usize) < lods.vertex_count()));
+
+    let coarsest = &lods.levels[lods.len() - 1];
+    println!(
+        "{:9}: {} triangles => {} LOD levels down to {} triangles (error {:.2e}), {} vertices in {:.2} msec",
+        "LodChain",
+        lods.level_indices(0).len() / 3,
+        lods.len(),
+        coarsest.index_range.len() / 3,
+        coarsest.error,
+        lods.vertex_count(),
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn lod_cluster_indices(
+    lods: &meshopt::ClusterLods,
+    clusters: impl Iterator<Item = usize>,
+) -> Vec<u32> {
+    let mut indices: Vec<u32> = Vec::new();
+    for cluster in clusters {
+        let meshlet = lods.meshlets.get(cluster);
+        indices.extend(
+            meshlet
+                .triangles
+                .iter()
+                .map(|&index| meshlet.vertices[index as usize]),
+        );
+    }
+    indices
+}
+
+fn cluster_lod(mesh: &Mesh) {
+    let vertex_adapter = mesh.vertex_adapter();
+
+    let process_start = Instant::now();
+    let lods = meshopt::ClusterDag::new(64, 124)
+        .build(&mesh.indices, &vertex_adapter)
+        .unwrap();
+    let process_elapsed = process_start.elapsed();
+
+    assert_eq!(lods.meshlets.len(), lods.len());
+    for cluster in &lods.clusters {
+        // errors and bounds must grow monotonically towards the roots
+        assert!(cluster.parent_bounds.error >= cluster.bounds.error);
+        if let Some(group) = cluster.group {
+            let parent = &lods.groups[group].bounds;
+            let d = [
+                parent.center[0] - cluster.bounds.center[0],
+                parent.center[1] - cluster.bounds.center[1],
+                parent.center[2] - cluster.bounds.center[2],
+            ];
+            let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+            assert!(distance + cluster.bounds.radius <= parent.radius * 1.001 + 1e-6);
+        }
+    }
+
+    // shared group borders are locked, so simplifying a group never adds border vertices and
+    // its clusters at the next level still match the neighboring groups
+    for group in &lods.groups {
+        let source = lod_cluster_indices(&lods, group.children.iter().copied());
+        let simplified = lod_cluster_indices(&lods, group.clusters.clone());
+        let source_border = meshopt::find_border_vertices(&source, &vertex_adapter);
+        let simplified_border = meshopt::find_border_vertices(&simplified, &vertex_adapter);
+        for &index in &simplified {
+            assert!(!simplified_border[index as usize] || source_border[index as usize]);
+        }
+    }
+
+    // with an error limit, no simplification step adds more error than the limit allows,
+    // relative to the extents of the group
+    let error_limit = 1e-2;
+    let limited = meshopt::ClusterDag::new(64, 124)
+        .error(error_limit)
+        .build(&mesh.indices, &vertex_adapter)
+        .unwrap();
+    for group in &limited.groups {
+        let source = lod_cluster_indices(&limited, group.children.iter().copied());
+        let extent = (0..3)
+            .map(|k| {
+                let values = source
+                    .iter()
+                    .map(|&index| mesh.vertices[index as usize].p[k]);
+                values.clone().fold(f32::MIN, f32::max) - values.fold(f32::MAX, f32::min)
+            })
+            .fold(0f32, f32::max);
+        let children_error = group
+            .children
+            .iter()
+            .map(|&child| limited.clusters[child].bounds.error)
+            .fold(0f32, f32::max);
+        assert!(group.bounds.error <= children_error.max(error_limit * extent * 1.001));
+    }
+    assert!(meshopt::ClusterDag::new(64, 124)
+        .error(-1.0)
+        .build(&mesh.indices, &vertex_adapter)
+        .is_err());
+
+    let roots = lods
+        .clusters
+        .iter()
+        .filter(|cluster| cluster.group.is_none())
+        .count();
+    println!(
+        "{:9}: {} clusters in {} levels ({} groups, {} roots) in {:.2} msec",
+        "ClusterLod",
+        lods.len(),
+        lods.level_count(),
+        lods.groups.len(),
+        roots,
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn simplify_attributes(mesh: &Mesh, threshold: f32) {
+    let vertex_adapter = mesh.vertex_adapter();
+    let normals = mesh.vertices.iter().map(|v| v.n).collect::<Vec<[f32; 3]>>();
+    let tex_coords = mesh.vertices.iter().map(|v| v.t).collect::<Vec<[f32; 2]>>();
+    let target_index_count = (mesh.indices.len() as f32 * threshold) as usize / 3 * 3;
+
+    let process_start = Instant::now();
+    let (lod, error) = meshopt::simplify_with_attributes(
+        &mesh.indices,
+        &vertex_adapter,
+        &[
+            meshopt::AttributeStream::new(&normals, 0.5),
+            meshopt::AttributeStream::new(&tex_coords, 1.0),
+        ],
+        target_index_count,
+        1e-2,
+    )
+    .unwrap();
+    let process_elapsed = process_start.elapsed();
+
+    assert!(lod.len() <= mesh.indices.len());
+
+    println!(
+        "{:9}: {} triangles => {} triangles (error {:.2e}) in {:.2} msec",
+        "SimplifyA",
+        mesh.indices.len() / 3,
+        lod.len() / 3,
+        error,
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn simplify_attributes_boundary() {
+    // a flat plane with a sharp color change between columns 16 and 17; positions alone would
+    // collapse it to a few triangles that blend the colors across the whole plane
+    let plane = Mesh::create_plane(32);
+    let colors = plane
+        .vertices
+        .iter()
+        .map(|v| [if v.p[0] <= 16.0 { 0.0 } else { 1.0 }])
+        .collect::<Vec<[f32; 1]>>();
+
+    let (lod, _) = meshopt::simplify_with_attributes_decoder(
+        &plane.indices,
+        &plane.vertices,
+        &[meshopt::AttributeStream::new(&colors, 1.0)],
+        0,
+        1e-2,
+    )
+    .unwrap();
+    let (flat, _) = meshopt::simplify_decoder_with_error(&plane.indices, &plane.vertices, 0, 1e-2);
+    assert!(lod.len() < plane.indices.len());
+    assert!(flat.len() < lod.len());
+
+    // the boundary survives, and colors are still only blended inside the original band
+    let mut blended = 0;
+    for triangle in lod.chunks_exact(3) {
+        let color = |i: usize| colors[triangle[i] as usize][0];
+        if (0..3).any(|i| color(i) == 0.0) && (0..3).any(|i| color(i) == 1.0) {
+            blended += 1;
+            for &index in triangle {
+                let x = plane.vertices[index as usize].p[0];
+                assert!(x == 16.0 || x == 17.0);
+            }
+        }
+    }
+    assert!(blended > 0);
+}
+
+fn simplify_chunk(mesh: &Mesh, threshold: f32) {
+    // simplify the first half of the mesh on its own, as if it was a separate streaming chunk
+    let chunk = &mesh.indices[..mesh.indices.len() / 6 * 3];
+    let vertex_adapter = mesh.vertex_adapter();
+    let target_index_count = (chunk.len() as f32 * threshold) as usize / 3 * 3;
+
+    let process_start = Instant::now();
+    let border = meshopt::find_border_vertices(chunk, &vertex_adapter);
+    let (lod, error) =
+        meshopt::simplify_with_locks(chunk, &vertex_adapter, &border, target_index_count, 1e-2)
+            .unwrap();
+    let process_elapsed = process_start.elapsed();
+
+    // locked vertices are never moved, so every border position is still part of the result
+    let used = lod
+        .iter()
+        .map(|&index| mesh.vertices[index as usize].p.map(f32::to_bits))
+        .collect::<HashSet<[u32; 3]>>();
+    for &index in chunk {
+        let vertex = &mesh.vertices[index as usize];
+        assert!(!border[index as usize] || used.contains(&vertex.p.map(f32::to_bits)));
+    }
+
+    println!(
+        "{:9}: {} triangles => {} triangles ({} border vertices, error {:.2e}) in {:.2} msec",
+        "SimplifyL",
+        chunk.len() / 3,
+        lod.len() / 3,
+        border.iter().filter(|&&locked| locked).count(),
+        error,
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn simplify_options(mesh: &Mesh, threshold: f32) {
+    let vertex_adapter = mesh.vertex_adapter();
+    // allow 1% of the mesh extents, expressed in world space units
+    let target_error = 1e-2 * meshopt::simplify_scale(&vertex_adapter);
+    let options = meshopt::SimplifyOptions::new()
+        .target_ratio(threshold)
+        .target_error(target_error)
+        .absolute_error(true)
+        .lock_border(true)
+        .max_iterations(3)
+        .return_error(true);
+
+    let process_start = Instant::now();
+    let result = meshopt::simplify_with(&mesh.indices, &vertex_adapter, &options).unwrap();
+    let process_elapsed = process_start.elapsed();
+
+    let error = result.error.unwrap();
+    assert!(error <= target_error * 1.001);
+    assert!(result.indices.len() <= mesh.indices.len());
+    assert!(meshopt::simplify_with(&mesh.indices, &vertex_adapter, &options.sloppy(true)).is_err());
+    assert!(meshopt::simplify_with(
+        &mesh.indices,
+        &vertex_adapter,
+        &options.lock_border(false).target_ratio(1.5)
+    )
+    .is_err());
+    assert!(meshopt::simplify_with(
+        &mesh.indices,
+        &vertex_adapter,
+        &options.target_error(f32::NAN)
+    )
+    .is_err());
+
+    println!(
+        "{:9}: {} triangles => {} triangles (error {:.2e}) in {:.2} msec",
+        "SimplifyO",
+        mesh.indices.len() / 3,
+        result.indices.len() / 3,
+        error,
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn simplify_compact(mesh: &Mesh, threshold: f32) {
+    let options = meshopt::SimplifyOptions::new().target_ratio(threshold);
+
+    let process_start = Instant::now();
+    let (vertices, indices) =
+        meshopt::simplify_compact(&mesh.indices, &mesh.vertices, &options, true).unwrap();
+    let process_elapsed = process_start.elapsed();
+
+    // every vertex of the compacted mesh is referenced, and the triangles match the plain result
+    let mut used = vec![false; vertices.len()];
+    for &index in &indices {
+        used[index as usize] = true;
+    }
+    assert!(used.iter().all(|&used| used));
+    let lod = meshopt::simplify_decoder_with(&mesh.indices, &mesh.vertices, &options)
+        .unwrap()
+        .indices;
+    assert_eq!(lod.len(), indices.len());
+    let (plain_vertices, plain_indices) =
+        meshopt::simplify_compact(&mesh.indices, &mesh.vertices, &options, false).unwrap();
+    assert_eq!(plain_vertices.len(), vertices.len());
+    assert_eq!(plain_indices.len(), indices.len());
+
+    println!(
+        "{:9}: {} triangles, {} vertices => {} triangles, {} vertices in {:.2} msec",
+        "SimplifyC",
+        mesh.indices.len() / 3,
+        mesh.vertices.len(),
+        indices.len() / 3,
+        vertices.len(),
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn simplify_points(mesh: &Mesh, threshold: f32) {
+    let vertex_adapter = mesh.vertex_adapter();
+    let target_vertex_count = (mesh.vertices.len() as f32 * threshold) as usize;
+
+    let process_start = Instant::now();
+    let points = meshopt::simplify_points(&vertex_adapter, target_vertex_count);
+    let process_elapsed = process_start.elapsed();
+
+    assert!(points.len() <= target_vertex_count);
+    assert!(points
+        .iter()
+        .all(|&point| (point as usize) < mesh.vertices.len()));
+
+    println!(
+        "{:9}: {} points => {} points in {:.2} msec",
+        "SimplifyP",
+        mesh.vertices.len(),
+        points.len(),
+        elapsed_to_ms(process_elapsed),
+    );
+}
+
+fn encode_index(mesh: &Mesh, options: EncodeOptions) {
     let encode_start = Instant::now();
-    let encoded = meshopt::encode_index_buffer(&mesh.indices, mesh.vertices.len()).unwrap();
+    let encoded =
+        meshopt::encode_index_buffer_with(&mesh.indices, mesh.vertices.len(), options).unwrap();
     let encode_elapsed = encode_start.elapsed();
 
+    let version = meshopt::decode_index_version(&encoded).unwrap();
+    assert_eq!(Some(version), options.version);
+    // an explicit version only applies to that call, the default encoder is left untouched
+    let default_version = meshopt::decode_index_version(
+        &meshopt::encode_index_buffer(&mesh.indices, mesh.vertices.len()).unwrap(),
+    )
+    .unwrap();
+    let encoded_again =
+        meshopt::encode_index_buffer_with(&mesh.indices, mesh.vertices.len(), options).unwrap();
+    assert_eq!(encoded, encoded_again);
+    assert_eq!(
+        meshopt::decode_index_version(
+            &meshopt::encode_index_buffer(&mesh.indices, mesh.vertices.len()).unwrap()
+        )
+        .unwrap(),
+        default_version
+    );
+
     let decode_start = Instant::now();
     let decoded = meshopt::decode_index_buffer::<u32>(&encoded, mesh.indices.len()).unwrap();
     let decode_elapsed = decode_start.elapsed();
@@ -797,7 +1740,8 @@ fn encode_index(mesh: &Mesh) {
     }
 
     println!(
-        "IdxCodec : {:.1} bits/triangle (post-deflate {:.1} bits/triangle); encode {:.2} msec, decode {:.2} msec ({:.2} GB/s)",
+        "IdxCodec{:1}: {:.1} bits/triangle (post-deflate {:.1} bits/triangle); encode {:.2} msec, decode {:.2} msec ({:.2} GB/s)",
+        version,
         (encoded.len() * 8) as f64 / (mesh.indices.len() / 3) as f64,
         (compressed.len() * 8) as f64 / (mesh.indices.len() / 3) as f64,
         elapsed_to_ms(encode_elapsed),
@@ -806,6 +1750,46 @@ fn encode_index(mesh: &Mesh) {
     );
 }
 
+fn encode_index_sequence(data: &[u32], vertex_count: usize, desc: &str) {
+    let encode_start = Instant::now();
+    let encoded = meshopt::encode_index_sequence(data, vertex_count).unwrap();
+    let encode_elapsed = encode_start.elapsed();
+
+    let decode_start = Instant::now();
+    let decoded = meshopt::decode_index_sequence::<u32>(&encoded, data.len()).unwrap();
+    let decode_elapsed = decode_start.elapsed();
+
+    assert_eq!(data, &decoded[..]);
+
+    if vertex_count <= 65536 {
+        let decoded2 = meshopt::decode_index_sequence::<u16>(&encoded, data.len()).unwrap();
+        for (index, index16) in decoded.iter().zip(&decoded2) {
+            assert_eq!(*index, u32::from(*index16));
+        }
+    }
+
+    let compressed = compress(&encoded);
+
+    println!(
+        "IdxCodec{:1}: {:.1} bits/index (post-deflate {:.1} bits/index); encode {:.2} msec, decode {:.2} msec ({:.2} GB/s)",
+        desc,
+        (encoded.len() * 8) as f64 / data.len() as f64,
+        (compressed.len() * 8) as f64 / data.len() as f64,
+        elapsed_to_ms(encode_elapsed),
+        elapsed_to_ms(decode_elapsed),
+        ((decoded.len() * 4) as f64 / (1 << 30) as f64) / (elapsed_to_ms(decode_elapsed) as f64 / 1000.0),
+    );
+}
+
+fn encode_index_restart() {
+    // two strips separated by restart indices, as produced by `stripify` with restart enabled
+    let restart = u32::MAX;
+    let strip = [0, 1, 2, 3, restart, 4, 5, 6, 7, 8, restart, 2, 9, 3];
+    let encoded = meshopt::encode_index_sequence(&strip, 10).unwrap();
+    let decoded = meshopt::decode_index_sequence::<u32>(&encoded, strip.len()).unwrap();
+    assert_eq!(&strip[..], &decoded[..]);
+}
+
 fn encode_vertex<T: FromVertex + Clone + Default + Eq>(mesh: &Mesh, name: &str) {
     let packed = pack_vertices::<T>(&mesh.vertices);
 
@@ -832,6 +1816,133 @@ fn encode_vertex<T: FromVertex + Clone + Default + Eq>(mesh: &Mesh, name: &str)
     );
 }
 
+fn encode_filter_oct(mesh: &Mesh, bits: u32) {
+    let normals: Vec<[f32; 4]> = mesh
+        .vertices
+        .iter()
+        .map(|vertex| [vertex.n[0], vertex.n[1], vertex.n[2], 1.0])
+        .collect();
+
+    let encode_start = Instant::now();
+    let filtered = meshopt::encode_filter_oct::<i16>(&normals, bits).unwrap();
+    let encoded = meshopt::encode_vertex_buffer(&filtered).unwrap();
+    let encode_elapsed = encode_start.elapsed();
+
+    let decode_start = Instant::now();
+    let mut decoded: Vec<[i16; 4]> =
+        meshopt::decode_vertex_buffer(&encoded, mesh.vertices.len()).unwrap();
+    meshopt::decode_filter_oct(&mut decoded);
+    let decode_elapsed = decode_start.elapsed();
+
+    let mut max_error = 0f32;
+    for (normal, packed) in normals.iter().zip(&decoded) {
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        if length == 0.0 {
+            continue;
+        }
+        for k in 0..3 {
+            let error = (normal[k] / length - packed[k] as f32 / 32767.0).abs();
+            max_error = max_error.max(error);
+        }
+    }
+
+    assert!(max_error < 1e-2);
+
+    let compressed = compress(&encoded);
+
+    println!(
+        "VtxFilter: oct{} {:.1} bits/normal (post-deflate {:.1} bits/normal), max error {:.5}; encode {:.2} msec, decode {:.2} msec",
+        bits,
+        (encoded.len() * 8) as f64 / (mesh.vertices.len()) as f64,
+        (compressed.len() * 8) as f64 / (mesh.vertices.len()) as f64,
+        max_error,
+        elapsed_to_ms(encode_elapsed),
+        elapsed_to_ms(decode_elapsed),
+    );
+}
+
+fn encode_filter_quat(mesh: &Mesh, bits: u32) {
+    // rotate around the vertex normal by a varying angle to get a spread of unit quaternions
+    let rotations: Vec<[f32; 4]> = mesh
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, vertex)| {
+            let n = vertex.n;
+            let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            let axis = if length > 0.0 {
+                [n[0] / length, n[1] / length, n[2] / length]
+            } else {
+                [0.0, 0.0, 1.0]
+            };
+            let (s, c) = (i as f32 * 0.37).sin_cos();
+            [axis[0] * s, axis[1] * s, axis[2] * s, c]
+        })
+        .collect();
+
+    let filtered = meshopt::encode_filter_quat(&rotations, bits).unwrap();
+    let encoded = meshopt::encode_vertex_buffer(&filtered).unwrap();
+
+    let mut decoded: Vec<[i16; 4]> =
+        meshopt::decode_vertex_buffer(&encoded, mesh.vertices.len()).unwrap();
+    meshopt::decode_filter_quat(&mut decoded);
+
+    // q and -q encode the same rotation, so compare against the closer of the two
+    let mut max_error = 0f32;
+    for (rotation, packed) in rotations.iter().zip(&decoded) {
+        let mut error_pos = 0f32;
+        let mut error_neg = 0f32;
+        for k in 0..4 {
+            let value = packed[k] as f32 / 32767.0;
+            error_pos = error_pos.max((rotation[k] - value).abs());
+            error_neg = error_neg.max((rotation[k] + value).abs());
+        }
+        max_error = max_error.max(error_pos.min(error_neg));
+    }
+
+    assert!(max_error < 1e-3);
+
+    println!(
+        "VtxFilter: quat{} {:.1} bits/rotation, max error {:.5}",
+        bits,
+        (encoded.len() * 8) as f64 / (mesh.vertices.len()) as f64,
+        max_error,
+    );
+}
+
+fn encode_filter_exp(mesh: &Mesh, bits: u32) {
+    let positions: Vec<[f32; 3]> = mesh.vertices.iter().map(|vertex| vertex.p).collect();
+
+    let filtered = meshopt::encode_filter_exp(&positions, bits).unwrap();
+    let encoded = meshopt::encode_vertex_buffer(&filtered).unwrap();
+
+    let mut decoded: Vec<[f32; 3]> =
+        meshopt::decode_vertex_buffer(&encoded, mesh.vertices.len()).unwrap();
+    meshopt::decode_filter_exp(&mut decoded).unwrap();
+
+    // the native filter is limited to vectors of 64 components
+    assert!(meshopt::encode_filter_exp(&[[0f32; 64]], bits).is_ok());
+    assert!(meshopt::encode_filter_exp(&[[0f32; 65]], bits).is_err());
+
+    // the exponent is shared by the vector, so the error is bounded relative to its largest component
+    let mut max_error = 0f32;
+    for (position, value) in positions.iter().zip(&decoded) {
+        let scale = position.iter().fold(0f32, |m, v| m.max(v.abs()));
+        for k in 0..3 {
+            let error = (position[k] - value[k]).abs();
+            assert!(error <= scale / (1 << (bits - 2)) as f32);
+            max_error = max_error.max(error);
+        }
+    }
+
+    println!(
+        "VtxFilter: exp{} {:.1} bits/position, max error {:.5}",
+        bits,
+        (encoded.len() * 8) as f64 / (mesh.vertices.len()) as f64,
+        max_error,
+    );
+}
+
 fn pack_mesh<T: FromVertex + Clone + Default>(mesh: &Mesh, name: &str) {
     let vertices = pack_vertices::<T>(&mesh.vertices);
     let compressed = compress(&vertices);
@@ -869,6 +1980,9 @@ fn process(path: Option<PathBuf>, export: bool) {
     optimize_mesh(&mesh, "Overdraw", opt_overdraw);
     optimize_mesh(&mesh, "Fetch", opt_fetch);
     optimize_mesh(&mesh, "FetchMap", opt_fetch_remap);
+    optimize_mesh(&mesh, "Spatial", opt_spatial_sort);
+    optimize_mesh(&mesh, "SpatialT", opt_spatial_sort_triangles);
+    optimize_mesh(&mesh, "SpatialTC", opt_spatial_sort_triangles_cache);
     optimize_mesh(&mesh, "Complete", opt_complete);
 
     let mut copy = mesh.clone();
@@ -888,18 +2002,57 @@ fn process(path: Option<PathBuf>, export: bool) {
         }
     }
 
-    stripify(&copy, false);
-    stripify(&copy, true);
+    // restart indices join strips with one index instead of a pair of degenerate triangles
+    let strip_length = stripify(&copy, false, " ");
+    let strip_length_restart = stripify(&copy, true, "R");
+    assert!(strip_length_restart <= strip_length);
 
-    meshlets(&copy);
-    shadow(&copy);
+    // strip-oriented vertex cache optimization trades vertex cache efficiency for shorter strips
+    let mut copy_strip = mesh.clone();
+    meshopt::optimize_vertex_cache_strip_in_place(
+        &mut copy_strip.indices,
+        copy_strip.vertices.len(),
+    );
+    meshopt::optimize_vertex_fetch_in_place(&mut copy_strip.indices, &mut copy_strip.vertices);
 
-    encode_index(&copy);
+    let strip_length_strip = stripify(&copy_strip, true, "S");
+    assert!(strip_length_strip <= strip_length_restart);
+
+    meshlets(&copy, false);
+    meshlets(&copy, true);
+    cull_known_answers();
+    shadow(&copy);
+    adjacency(&copy);
+    adjacency_closed_open();
+    tessellation(&copy);
+    tessellation_layout();
+    indices_16(&copy);
+    indices_16_boundary();
+
+    encode_index(&copy, EncodeOptions::new(0));
+    encode_index(&copy, EncodeOptions::new(1));
+
+    let strip = meshopt::stripify(&copy.indices, copy.vertices.len(), 0).unwrap();
+    encode_index_sequence(&strip, copy.vertices.len(), "S");
+    let strip = meshopt::stripify(&copy.indices, copy.vertices.len(), u32::MAX).unwrap();
+    encode_index_sequence(&strip, copy.vertices.len(), "R");
+    encode_index_restart();
     pack_mesh::<PackedVertex>(&copy, "");
     encode_vertex::<PackedVertex>(&copy, "");
     encode_vertex::<PackedVertexOct>(&copy, "0");
+    encode_filter_oct(&copy, 12);
+    encode_filter_quat(&copy, 12);
+    encode_filter_exp(&copy, 15);
 
     simplify(&mesh);
+    simplify_lod_chain(&mesh);
+    simplify_points(&mesh, 0.2);
+    simplify_attributes(&mesh, 0.2);
+    simplify_attributes_boundary();
+    simplify_chunk(&mesh, 0.2);
+    simplify_options(&mesh, 0.2);
+    simplify_compact(&mesh, 0.2);
+    cluster_lod(&copy);
 }
 
 fn main() {
@@ -376,6 +376,14 @@ extern "C" {
         stride: usize,
     );
 }
+#[doc = " When encoding exponents, use separate values for each component (maximum quality)"]
+pub const meshopt_EncodeExpMode_meshopt_EncodeExpSeparate: meshopt_EncodeExpMode = 0;
+#[doc = " When encoding exponents, use shared value for all components of each vector (better compression)"]
+pub const meshopt_EncodeExpMode_meshopt_EncodeExpSharedVector: meshopt_EncodeExpMode = 1;
+#[doc = " When encoding exponents, use shared value for each component of all vectors (best compression)"]
+pub const meshopt_EncodeExpMode_meshopt_EncodeExpSharedComponent: meshopt_EncodeExpMode = 2;
+#[doc = " Experimental: Exponential filter encoder modes"]
+pub type meshopt_EncodeExpMode = ::std::os::raw::c_uint;
 extern "C" {
     #[doc = " Vertex buffer filter encoders"]
     #[doc = " These functions can be used to encode data in a format that meshopt_decodeFilter can decode"]
@@ -389,9 +397,8 @@ extern "C" {
     #[doc = " Input data must contain 4 floats for every quaternion (count*4 total)."]
     #[doc = ""]
     #[doc = " meshopt_encodeFilterExp encodes arbitrary (finite) floating-point data with 8-bit exponent and K-bit integer mantissa (1 <= K <= 24)."]
-    #[doc = " Mantissa is shared between all components of a given vector as defined by stride; stride must be divisible by 4."]
+    #[doc = " Exponent can be shared between all components of a given vector as defined by stride or all values of a given component; stride must be divisible by 4."]
     #[doc = " Input data must contain stride/4 floats for every vector (count*stride/4 total)."]
-    #[doc = " When individual (scalar) encoding is desired, simply pass stride=4 and adjust count accordingly."]
     pub fn meshopt_encodeFilterOct(
         destination: *mut ::std::os::raw::c_void,
         count: usize,
@@ -416,20 +423,30 @@ extern "C" {
         stride: usize,
         bits: ::std::os::raw::c_int,
         data: *const f32,
+        mode: meshopt_EncodeExpMode,
     );
 }
+#[doc = " Do not move vertices that are located on the topological border (vertices on triangle edges that don't have a paired triangle). Useful for simplifying portions of the larger mesh."]
+pub const meshopt_SimplifyLockBorder: _bindgen_ty_1 = 1;
+#[doc = " Improve simplification performance assuming input indices are a sparse subset of the mesh. Note that error becomes relative to subset extents."]
+pub const meshopt_SimplifySparse: _bindgen_ty_1 = 2;
+#[doc = " Treat error limit and resulting error as absolute instead of relative to mesh extents."]
+pub const meshopt_SimplifyErrorAbsolute: _bindgen_ty_1 = 4;
+#[doc = " Simplification options"]
+pub type _bindgen_ty_1 = ::std::os::raw::c_uint;
 extern "C" {
-    #[doc = " Experimental: Mesh simplifier"]
+    #[doc = " Mesh simplifier"]
     #[doc = " Reduces the number of triangles in the mesh, attempting to preserve mesh appearance as much as possible"]
     #[doc = " The algorithm tries to preserve mesh topology and can stop short of the target goal based on topology constraints or target error."]
-    #[doc = " If not all attributes from the input mesh are required, it's recommended to reindex the mesh using meshopt_generateShadowIndexBuffer prior to simplification."]
+    #[doc = " If not all attributes from the input mesh are required, it's recommended to reindex the mesh without them prior to simplification."]
     #[doc = " Returns the number of indices after simplification, with destination containing new index data"]
     #[doc = " The resulting index buffer references vertices from the original vertex buffer."]
     #[doc = " If the original vertex data isn't required, creating a compact vertex buffer using meshopt_optimizeVertexFetch is recommended."]
     #[doc = ""]
     #[doc = " destination must contain enough space for the target index buffer, worst case is index_count elements (*not* target_index_count)!"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex - similar to glVertexPointer"]
-    #[doc = " target_error represents the error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation"]
+    #[doc = " target_error represents the error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation; value range [0..1]"]
+    #[doc = " options must be a bitmask composed of meshopt_SimplifyX options; 0 is a safe default"]
     #[doc = " result_error can be NULL; when it's not NULL, it will contain the resulting (relative) error after simplification"]
     pub fn meshopt_simplify(
         destination: *mut ::std::os::raw::c_uint,
@@ -440,6 +457,34 @@ extern "C" {
         vertex_positions_stride: usize,
         target_index_count: usize,
         target_error: f32,
+        options: ::std::os::raw::c_uint,
+        result_error: *mut f32,
+    ) -> usize;
+}
+extern "C" {
+    #[doc = " Experimental: Mesh simplifier with attribute metric"]
+    #[doc = " The algorithm enhances meshopt_simplify by incorporating attribute values into the error metric used to prioritize simplification order; see meshopt_simplify documentation for details."]
+    #[doc = " Note that the number of attributes affects memory requirements and running time; this algorithm requires ~1.5x more memory and time compared to meshopt_simplify when using 4 scalar attributes."]
+    #[doc = ""]
+    #[doc = " vertex_attributes should have attribute_count floats for each vertex"]
+    #[doc = " attribute_weights should have attribute_count floats in total; the weights determine relative priority of attributes between each other and wrt position"]
+    #[doc = " attribute_count must be <= 16"]
+    #[doc = " vertex_lock can be NULL; when it's not NULL, it should have a value for each vertex; 1 denotes vertices that can't be moved"]
+    pub fn meshopt_simplifyWithAttributes(
+        destination: *mut ::std::os::raw::c_uint,
+        indices: *const ::std::os::raw::c_uint,
+        index_count: usize,
+        vertex_positions: *const f32,
+        vertex_count: usize,
+        vertex_positions_stride: usize,
+        vertex_attributes: *const f32,
+        vertex_attributes_stride: usize,
+        attribute_weights: *const f32,
+        attribute_count: usize,
+        vertex_lock: *const ::std::os::raw::c_uchar,
+        target_index_count: usize,
+        target_error: f32,
+        options: ::std::os::raw::c_uint,
         result_error: *mut f32,
     ) -> usize;
 }
@@ -476,11 +521,16 @@ extern "C" {
     #[doc = ""]
     #[doc = " destination must contain enough space for the target index buffer (target_vertex_count elements)"]
     #[doc = " vertex_positions should have float3 position in the first 12 bytes of each vertex - similar to glVertexPointer"]
+    #[doc = " vertex_colors can be NULL; when it's not NULL, it should have float3 color in the first 12 bytes of each vertex"]
+    #[doc = " color_weight determines relative priority of color wrt position; 1.0 is a safe default"]
     pub fn meshopt_simplifyPoints(
         destination: *mut ::std::os::raw::c_uint,
         vertex_positions: *const f32,
         vertex_count: usize,
         vertex_positions_stride: usize,
+        vertex_colors: *const f32,
+        vertex_colors_stride: usize,
+        color_weight: f32,
         target_vertex_count: usize,
     ) -> usize;
 }
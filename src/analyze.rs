@@ -1,4 +1,4 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter};
+use crate::{ffi, indices_to_u32, DecodePosition, Index, Meshlets, VertexDataAdapter};
 use std::mem;
 
 pub type VertexCacheStatistics = ffi::meshopt_VertexCacheStatistics;
@@ -7,13 +7,14 @@ pub type OverdrawStatistics = ffi::meshopt_OverdrawStatistics;
 
 /// Returns cache hit statistics using a simplified FIFO model.
 /// Results may not match actual GPU performance.
-pub fn analyze_vertex_cache(
-    indices: &[u32],
+pub fn analyze_vertex_cache<I: Index>(
+    indices: &[I],
     vertex_count: usize,
     cache_size: u32,
     warp_size: u32,
     prim_group_size: u32,
 ) -> VertexCacheStatistics {
+    let indices = indices_to_u32(indices);
     unsafe {
         ffi::meshopt_analyzeVertexCache(
             indices.as_ptr(),
@@ -28,11 +29,12 @@ pub fn analyze_vertex_cache(
 
 /// Returns cache hit statistics using a simplified direct mapped model.
 /// Results may not match actual GPU performance.
-pub fn analyze_vertex_fetch(
-    indices: &[u32],
+pub fn analyze_vertex_fetch<I: Index>(
+    indices: &[I],
     vertex_count: usize,
     vertex_size: usize,
 ) -> VertexFetchStatistics {
+    let indices = indices_to_u32(indices);
     unsafe {
         ffi::meshopt_analyzeVertexFetch(indices.as_ptr(), indices.len(), vertex_count, vertex_size)
     }
@@ -41,9 +43,10 @@ pub fn analyze_vertex_fetch(
 /// Returns overdraw statistics using a software rasterizer.
 /// Results may not match actual GPU performance.
 pub fn analyze_overdraw_decoder<T: DecodePosition>(
-    indices: &[u32],
+    indices: &[impl Index],
     vertices: &[T],
 ) -> OverdrawStatistics {
+    let indices = indices_to_u32(indices);
     let positions = vertices
         .iter()
         .map(|vertex| vertex.decode_position())
@@ -61,7 +64,11 @@ pub fn analyze_overdraw_decoder<T: DecodePosition>(
 
 /// Returns overdraw statistics using a software rasterizer.
 /// Results may not match actual GPU performance.
-pub fn analyze_overdraw(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> OverdrawStatistics {
+pub fn analyze_overdraw<I: Index>(
+    indices: &[I],
+    vertices: &VertexDataAdapter<'_>,
+) -> OverdrawStatistics {
+    let indices = indices_to_u32(indices);
     unsafe {
         ffi::meshopt_analyzeOverdraw(
             indices.as_ptr(),
@@ -72,3 +79,139 @@ pub fn analyze_overdraw(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Ov
         )
     }
 }
+
+/// Number of buckets in `MeshletStatistics::cone_cutoff_histogram`.
+pub const CONE_CUTOFF_BUCKETS: usize = 8;
+
+/// Meshlet statistics returned by `analyze_meshlets`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct MeshletStatistics {
+    pub meshlet_count: u32,
+    pub triangle_count: u32,
+
+    pub vertices_min: u32,
+    pub vertices_max: u32,
+    pub vertices_avg: f32,
+    pub triangles_min: u32,
+    pub triangles_max: u32,
+    pub triangles_avg: f32,
+
+    /// Average vertex count relative to `max_vertices`, in [0..1].
+    pub vertex_utilization: f32,
+    /// Average triangle count relative to `max_triangles`, in [0..1].
+    pub triangle_utilization: f32,
+
+    /// Number of meshlets whose normal cone can reject them for some view (`cone_cutoff < 1`).
+    pub cone_cullable: u32,
+    /// Number of cullable meshlets by cone cutoff, in equal buckets over [0..1); lower cutoffs
+    /// mean narrower cones that are culled from more view directions.
+    pub cone_cutoff_histogram: [u32; CONE_CUTOFF_BUCKETS],
+
+    /// Average ratio of bounding sphere radius to half the diagonal of the meshlet bounding box;
+    /// values close to or below 1 indicate tight spheres.
+    pub sphere_tightness: f32,
+
+    /// Bytes used by the meshlet vertex and triangle buffers per triangle, with 4 byte vertex
+    /// indices and 3 byte triangles padded to 4 bytes per meshlet (12 for a 32-bit index buffer).
+    pub index_bytes_per_triangle: f32,
+}
+
+/// Returns statistics about the meshlets, to help with choosing meshlet limits and cone weight.
+///
+/// `max_vertices` and `max_triangles` should be the limits the meshlets were built with.
+pub fn analyze_meshlets(
+    meshlets: &Meshlets,
+    vertices: &VertexDataAdapter<'_>,
+    max_vertices: usize,
+    max_triangles: usize,
+) -> MeshletStatistics {
+    analyze_meshlets_positions(meshlets, &vertices.positions(), max_vertices, max_triangles)
+}
+
+/// Returns statistics about the meshlets, to help with choosing meshlet limits and cone weight.
+///
+/// `max_vertices` and `max_triangles` should be the limits the meshlets were built with.
+pub fn analyze_meshlets_decoder<T: DecodePosition>(
+    meshlets: &Meshlets,
+    vertices: &[T],
+    max_vertices: usize,
+    max_triangles: usize,
+) -> MeshletStatistics {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    analyze_meshlets_positions(meshlets, &positions, max_vertices, max_triangles)
+}
+
+fn analyze_meshlets_positions(
+    meshlets: &Meshlets,
+    positions: &[[f32; 3]],
+    max_vertices: usize,
+    max_triangles: usize,
+) -> MeshletStatistics {
+    let mut result = MeshletStatistics::default();
+    if meshlets.is_empty() {
+        return result;
+    }
+
+    result.meshlet_count = meshlets.len() as u32;
+    result.vertices_min = u32::MAX;
+    result.triangles_min = u32::MAX;
+
+    let mut vertex_count = 0usize;
+    let mut index_bytes = 0usize;
+    let mut tightness = 0f64;
+    let bounds = meshlets.compute_bounds_decoder(positions);
+
+    for (meshlet, bounds) in meshlets.iter().zip(&bounds) {
+        let meshlet_vertices = meshlet.vertices.len() as u32;
+        let meshlet_triangles = (meshlet.triangles.len() / 3) as u32;
+        vertex_count += meshlet.vertices.len();
+        result.triangle_count += meshlet_triangles;
+        result.vertices_min = result.vertices_min.min(meshlet_vertices);
+        result.vertices_max = result.vertices_max.max(meshlet_vertices);
+        result.triangles_min = result.triangles_min.min(meshlet_triangles);
+        result.triangles_max = result.triangles_max.max(meshlet_triangles);
+        index_bytes += meshlet.vertices.len() * 4 + ((meshlet.triangles.len() + 3) & !3);
+
+        if bounds.cone_cutoff < 1.0 {
+            result.cone_cullable += 1;
+            let bucket = (bounds.cone_cutoff * CONE_CUTOFF_BUCKETS as f32) as usize;
+            result.cone_cutoff_histogram[bucket.min(CONE_CUTOFF_BUCKETS - 1)] += 1;
+        }
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for &vertex in meshlet.vertices {
+            let position = positions[vertex as usize];
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+        }
+        let extent = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+        let half_diagonal =
+            (extent[0] * extent[0] + extent[1] * extent[1] + extent[2] * extent[2]).sqrt() * 0.5;
+        tightness += if half_diagonal > 0.0 {
+            f64::from(bounds.radius / half_diagonal)
+        } else {
+            1.0
+        };
+    }
+
+    let meshlet_count = meshlets.len() as f64;
+    result.vertices_avg = (vertex_count as f64 / meshlet_count) as f32;
+    result.triangles_avg = (f64::from(result.triangle_count) / meshlet_count) as f32;
+    result.vertex_utilization =
+        (f64::from(result.vertices_avg) / max_vertices.max(1) as f64) as f32;
+    result.triangle_utilization =
+        (f64::from(result.triangles_avg) / max_triangles.max(1) as f64) as f32;
+    result.sphere_tightness = (tightness / meshlet_count) as f32;
+    result.index_bytes_per_triangle = if result.triangle_count > 0 {
+        (index_bytes as f64 / f64::from(result.triangle_count)) as f32
+    } else {
+        0.0
+    };
+    result
+}
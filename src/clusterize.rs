@@ -1,14 +1,134 @@
 use crate::ffi;
-use crate::{DecodePosition, VertexDataAdapter};
+use crate::{
+    positions_adapter, spatial_sort_remap_decoder, typed_to_bytes, DecodePosition, Error, Result,
+    VertexDataAdapter,
+};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 
 pub type Bounds = ffi::meshopt_Bounds;
 
+#[inline(always)]
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[inline(always)]
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// A plane satisfying `dot(normal, p) + distance = 0`, with the normal pointing towards the
+/// inside of the volume it bounds.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Plane {
+    /// Unit length plane normal.
+    pub normal: [f32; 3],
+    /// Signed distance of the plane from the origin along the negated normal.
+    pub distance: f32,
+}
+
+impl Plane {
+    pub fn new(normal: [f32; 3], distance: f32) -> Self {
+        Self { normal, distance }
+    }
+
+    /// Returns the signed distance of `point` to the plane, positive on the inside.
+    #[inline]
+    pub fn signed_distance(&self, point: [f32; 3]) -> f32 {
+        dot(self.normal, point) + self.distance
+    }
+}
+
+impl Bounds {
+    /// Returns true if the cluster is backfacing for a perspective camera at `camera_position`,
+    /// using the normal cone apex:
+    ///   `dot(normalize(cone_apex - camera_position), cone_axis) >= cone_cutoff`
+    pub fn is_backfacing(&self, camera_position: [f32; 3]) -> bool {
+        let view = sub(self.cone_apex, camera_position);
+        let length = dot(view, view).sqrt();
+        dot(view, self.cone_axis) >= self.cone_cutoff * length
+    }
+
+    /// Returns true if the cluster is backfacing for a perspective camera at `camera_position`,
+    /// using the bounding sphere instead of the normal cone apex:
+    ///   `dot(center - camera_position, cone_axis) >= cone_cutoff * length(center - camera_position) + radius`
+    pub fn is_backfacing_sphere(&self, camera_position: [f32; 3]) -> bool {
+        let view = sub(self.center, camera_position);
+        let length = dot(view, view).sqrt();
+        dot(view, self.cone_axis) >= self.cone_cutoff * length + self.radius
+    }
+
+    /// Returns true if the cluster is backfacing for an orthographic camera looking along
+    /// `view_direction` (which should be normalized):
+    ///   `dot(view_direction, cone_axis) >= cone_cutoff`
+    pub fn is_backfacing_ortho(&self, view_direction: [f32; 3]) -> bool {
+        dot(view_direction, self.cone_axis) >= self.cone_cutoff
+    }
+
+    /// Returns true if the bounding sphere is at least partially inside all `planes`, which
+    /// must be normalized and point towards the inside of the frustum.
+    pub fn intersects_frustum(&self, planes: &[Plane; 6]) -> bool {
+        planes
+            .iter()
+            .all(|plane| plane.signed_distance(self.center) >= -self.radius)
+    }
+
+    /// Computes a conservative screen space rectangle of the bounding sphere, e.g. for testing
+    /// against a depth pyramid.
+    ///
+    /// `center_view` is the sphere center transformed to view space, looking along +Z, and
+    /// `p00`/`p11` are the first two diagonal elements of the projection matrix. The result is
+    /// `[min_x, min_y, max_x, max_y]` in normalized [0..1] screen coordinates with Y pointing
+    /// down, or `None` if the sphere intersects the `z_near` plane.
+    ///
+    /// See "2D Polyhedral Bounds of a Clipped, Perspective-Projected 3D Sphere" (JCGT 2013).
+    pub fn project_sphere(
+        &self,
+        center_view: [f32; 3],
+        z_near: f32,
+        p00: f32,
+        p11: f32,
+    ) -> Option<[f32; 4]> {
+        let [x, y, z] = center_view;
+        let r = self.radius;
+        if z < r + z_near {
+            return None;
+        }
+
+        // tangent points of the sphere in the XZ and YZ planes
+        let project = |c: f32| {
+            let (cx, cz) = (-c, -z);
+            let t = (cx * cx + cz * cz - r * r).sqrt();
+            let min = (t * cx - r * cz) / (r * cx + t * cz);
+            let max = (t * cx + r * cz) / (t * cz - r * cx);
+            (min, max)
+        };
+        let (min_x, max_x) = project(x);
+        let (min_y, max_y) = project(y);
+
+        Some([
+            min_x * p00 * 0.5 + 0.5,
+            max_y * p11 * -0.5 + 0.5,
+            max_x * p00 * 0.5 + 0.5,
+            min_y * p11 * -0.5 + 0.5,
+        ])
+    }
+}
+
+/// Maximum number of vertices per meshlet supported by the meshlet builders.
+pub const MESHLET_MAX_VERTICES: usize = 255;
+
+/// Maximum number of triangles per meshlet supported by the meshlet builders.
+pub const MESHLET_MAX_TRIANGLES: usize = 512;
+
 #[derive(Copy, Clone)]
 pub struct Meshlet<'data> {
     pub vertices: &'data [u32],
     pub triangles: &'data [u8],
 }
 
+#[derive(Debug, Default)]
 pub struct Meshlets {
     pub meshlets: Vec<ffi::meshopt_Meshlet>,
     vertices: Vec<u32>,
@@ -21,6 +141,11 @@ impl Meshlets {
         self.meshlets.len()
     }
 
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.meshlets.is_empty()
+    }
+
     fn meshlet_from_ffi(&self, meshlet: &ffi::meshopt_Meshlet) -> Meshlet<'_> {
         Meshlet {
             vertices: &self.vertices[meshlet.vertex_offset as usize
@@ -40,6 +165,527 @@ impl Meshlets {
             .iter()
             .map(|meshlet| self.meshlet_from_ffi(meshlet))
     }
+
+    /// Appends a meshlet with the given vertex indices and local triangle indices.
+    pub(crate) fn push(&mut self, vertices: &[u32], triangles: &[u8]) {
+        self.meshlets.push(ffi::meshopt_Meshlet {
+            vertex_offset: self.vertices.len() as u32,
+            triangle_offset: self.triangles.len() as u32,
+            vertex_count: vertices.len() as u32,
+            triangle_count: (triangles.len() / 3) as u32,
+        });
+        self.vertices.extend_from_slice(vertices);
+        self.triangles.extend_from_slice(triangles);
+        // keep the triangles of each meshlet 4-byte aligned, like the meshlet builders do
+        self.triangles.resize((self.triangles.len() + 3) & !3, 0);
+    }
+
+    /// Vertex indices of all meshlets, referenced by `meshopt_Meshlet::vertex_offset`.
+    #[inline]
+    pub fn vertices(&self) -> &[u32] {
+        &self.vertices
+    }
+
+    /// Micro index buffer of all meshlets, referenced by `meshopt_Meshlet::triangle_offset`.
+    #[inline]
+    pub fn triangles(&self) -> &[u8] {
+        &self.triangles
+    }
+
+    /// Releases the unused tail of the vertex and triangle buffers, which the meshlet builders
+    /// size for the worst case.
+    pub fn trim(&mut self) {
+        let vertex_count = self
+            .meshlets
+            .iter()
+            .map(|meshlet| (meshlet.vertex_offset + meshlet.vertex_count) as usize)
+            .max()
+            .unwrap_or(0);
+        let triangle_count = self
+            .meshlets
+            .iter()
+            .map(|meshlet| {
+                meshlet.triangle_offset as usize + ((meshlet.triangle_count as usize * 3 + 3) & !3)
+            })
+            .max()
+            .unwrap_or(0);
+
+        self.vertices.truncate(vertex_count);
+        self.vertices.shrink_to_fit();
+        self.triangles
+            .truncate(triangle_count.min(self.triangles.len()));
+        self.triangles.shrink_to_fit();
+    }
+
+    /// Computes culling data for all meshlets at once.
+    ///
+    /// With the `rayon` feature enabled, meshlets are processed in parallel.
+    pub fn compute_bounds(&self, vertices: &VertexDataAdapter<'_>) -> Vec<MeshletCullData> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            self.meshlets
+                .par_iter()
+                .map(|meshlet| {
+                    compute_meshlet_bounds(self.meshlet_from_ffi(meshlet), vertices).into()
+                })
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            self.iter()
+                .map(|meshlet| compute_meshlet_bounds(meshlet, vertices).into())
+                .collect()
+        }
+    }
+
+    /// Computes culling data for all meshlets at once, decoding the vertex positions only once.
+    pub fn compute_bounds_decoder<T: DecodePosition>(
+        &self,
+        vertices: &[T],
+    ) -> Vec<MeshletCullData> {
+        let positions = vertices
+            .iter()
+            .map(|vertex| vertex.decode_position())
+            .collect::<Vec<[f32; 3]>>();
+        self.compute_bounds(&positions_adapter(&positions))
+    }
+
+    /// Computes which meshlets share edges with each other.
+    ///
+    /// Vertices with the same position are treated as one, so that meshlets are connected across
+    /// attribute seams.
+    pub fn adjacency(&self, vertices: &VertexDataAdapter<'_>) -> MeshletAdjacency {
+        let remap = position_remap(&vertices.positions());
+        MeshletAdjacency::from_clusters(&self.cluster_indices(), &remap)
+    }
+
+    /// Computes which meshlets share edges with each other, like `adjacency`.
+    pub fn adjacency_decoder<T: DecodePosition>(&self, vertices: &[T]) -> MeshletAdjacency {
+        let positions = vertices
+            .iter()
+            .map(|vertex| vertex.decode_position())
+            .collect::<Vec<[f32; 3]>>();
+        let remap = position_remap(&positions);
+        MeshletAdjacency::from_clusters(&self.cluster_indices(), &remap)
+    }
+
+    /// Partitions the meshlets into spatially coherent groups of connected meshlets, each
+    /// holding roughly `group_size` meshlets.
+    ///
+    /// Groups are grown from seeds in spatial order by adding the neighbors sharing the most
+    /// edges with the group, and groups that end up less than half full are merged into an
+    /// adjacent group where possible. Returns the meshlet indices of each group.
+    pub fn partition(
+        &self,
+        vertices: &VertexDataAdapter<'_>,
+        group_size: usize,
+    ) -> Result<Vec<Vec<usize>>> {
+        self.partition_positions(&vertices.positions(), group_size)
+    }
+
+    /// Partitions the meshlets into groups of roughly `group_size` meshlets, like `partition`.
+    pub fn partition_decoder<T: DecodePosition>(
+        &self,
+        vertices: &[T],
+        group_size: usize,
+    ) -> Result<Vec<Vec<usize>>> {
+        let positions = vertices
+            .iter()
+            .map(|vertex| vertex.decode_position())
+            .collect::<Vec<[f32; 3]>>();
+        self.partition_positions(&positions, group_size)
+    }
+
+    fn partition_positions(
+        &self,
+        positions: &[[f32; 3]],
+        group_size: usize,
+    ) -> Result<Vec<Vec<usize>>> {
+        if group_size == 0 {
+            return Err(Error::Config("group size must be at least 1".to_string()));
+        }
+        let clusters = self.cluster_indices();
+        let adjacency = MeshletAdjacency::from_clusters(&clusters, &position_remap(positions));
+        let centers = self
+            .iter()
+            .map(|meshlet| {
+                let mut center = [0f32; 3];
+                for &vertex in meshlet.vertices {
+                    let position = positions[vertex as usize];
+                    for i in 0..3 {
+                        center[i] += position[i];
+                    }
+                }
+                center.map(|c| c / meshlet.vertices.len().max(1) as f32)
+            })
+            .collect::<Vec<[f32; 3]>>();
+        Ok(partition_clusters(&adjacency, &centers, group_size))
+    }
+
+    /// Returns the triangles of each meshlet as indices into the source vertex buffer.
+    fn cluster_indices(&self) -> Vec<Vec<u32>> {
+        self.iter()
+            .map(|meshlet| {
+                meshlet
+                    .triangles
+                    .iter()
+                    .map(|&index| meshlet.vertices[index as usize])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Packs the meshlets into tightly packed buffers that can be uploaded to the GPU as is.
+    ///
+    /// Vertex and triangle offsets of the resulting descriptors are rewritten to match the packed
+    /// buffers; see `MeshletTriangleFormat` for the units of `triangle_offset`.
+    pub fn pack(&self, format: MeshletTriangleFormat) -> PackedMeshlets {
+        let mut descriptors: Vec<ffi::meshopt_Meshlet> = Vec::with_capacity(self.meshlets.len());
+        let mut vertices: Vec<u32> = Vec::new();
+        let mut triangles: Vec<u32> = Vec::new();
+
+        for (descriptor, meshlet) in self.meshlets.iter().zip(self.iter()) {
+            let vertex_offset = vertices.len() as u32;
+            let triangle_offset = match format {
+                MeshletTriangleFormat::Bytes => triangles.len() as u32 * 4,
+                MeshletTriangleFormat::PackedU32 => triangles.len() as u32,
+            };
+
+            vertices.extend_from_slice(meshlet.vertices);
+            match format {
+                MeshletTriangleFormat::Bytes => {
+                    triangles.extend(meshlet.triangles.chunks(4).map(|chunk| {
+                        let mut word = [0u8; 4];
+                        word[..chunk.len()].copy_from_slice(chunk);
+                        u32::from_ne_bytes(word)
+                    }));
+                }
+                MeshletTriangleFormat::PackedU32 => {
+                    triangles.extend(meshlet.triangles.chunks_exact(3).map(|triangle| {
+                        u32::from(triangle[0])
+                            | u32::from(triangle[1]) << 8
+                            | u32::from(triangle[2]) << 16
+                    }));
+                }
+            }
+
+            descriptors.push(ffi::meshopt_Meshlet {
+                vertex_offset,
+                triangle_offset,
+                vertex_count: descriptor.vertex_count,
+                triangle_count: descriptor.triangle_count,
+            });
+        }
+
+        PackedMeshlets {
+            descriptors,
+            vertices,
+            triangles,
+            format,
+        }
+    }
+}
+
+/// Culling data of a single meshlet, laid out as three 16-byte vectors so that an array of
+/// them can be uploaded to the GPU as is (e.g. as a std430 storage buffer).
+///
+/// See `compute_cluster_bounds` for how to use the cone for backface culling.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct MeshletCullData {
+    /// Bounding sphere center.
+    pub center: [f32; 3],
+    /// Bounding sphere radius.
+    pub radius: f32,
+    /// Normal cone apex.
+    pub cone_apex: [f32; 3],
+    /// Normal cone cutoff, `cos(angle/2)`.
+    pub cone_cutoff: f32,
+    /// Normal cone axis.
+    pub cone_axis: [f32; 3],
+    /// Normal cone axis quantized to 8-bit SNORM.
+    pub cone_axis_s8: [i8; 3],
+    /// Normal cone cutoff quantized to 8-bit SNORM.
+    pub cone_cutoff_s8: i8,
+}
+
+impl From<Bounds> for MeshletCullData {
+    fn from(bounds: Bounds) -> Self {
+        Self {
+            center: bounds.center,
+            radius: bounds.radius,
+            cone_apex: bounds.cone_apex,
+            cone_cutoff: bounds.cone_cutoff,
+            cone_axis: bounds.cone_axis,
+            cone_axis_s8: bounds.cone_axis_s8,
+            cone_cutoff_s8: bounds.cone_cutoff_s8,
+        }
+    }
+}
+
+/// A meshlet sharing edges with another meshlet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MeshletNeighbor {
+    /// Index of the neighboring meshlet.
+    pub meshlet: usize,
+    /// Number of edges shared with the neighboring meshlet.
+    pub shared_edges: u32,
+}
+
+/// Shared edge adjacency graph of meshlets, computed by `Meshlets::adjacency`.
+#[derive(Debug, Clone)]
+pub struct MeshletAdjacency {
+    offsets: Vec<usize>,
+    neighbors: Vec<MeshletNeighbor>,
+}
+
+impl MeshletAdjacency {
+    /// Builds the graph for clusters given as triangle lists, with vertices mapped through
+    /// `position_remap`.
+    pub(crate) fn from_clusters<C: AsRef<[u32]>>(
+        clusters: &[C],
+        position_remap: &[u32],
+    ) -> MeshletAdjacency {
+        let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut weights: Vec<HashMap<usize, u32>> = vec![HashMap::new(); clusters.len()];
+        for (cluster, indices) in clusters.iter().enumerate() {
+            for triangle in indices.as_ref().chunks_exact(3) {
+                for i in 0..3 {
+                    let a = position_remap[triangle[i] as usize];
+                    let b = position_remap[triangle[(i + 1) % 3] as usize];
+                    let owner = *edges.entry((a.min(b), a.max(b))).or_insert(cluster);
+                    if owner != cluster {
+                        *weights[cluster].entry(owner).or_insert(0) += 1;
+                        *weights[owner].entry(cluster).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut offsets: Vec<usize> = Vec::with_capacity(clusters.len() + 1);
+        let mut neighbors: Vec<MeshletNeighbor> = Vec::new();
+        offsets.push(0);
+        for cluster_weights in weights {
+            let start = neighbors.len();
+            neighbors.extend(cluster_weights.into_iter().map(|(meshlet, shared_edges)| {
+                MeshletNeighbor {
+                    meshlet,
+                    shared_edges,
+                }
+            }));
+            neighbors[start..].sort_unstable_by_key(|neighbor| neighbor.meshlet);
+            offsets.push(neighbors.len());
+        }
+        MeshletAdjacency { offsets, neighbors }
+    }
+
+    /// Returns the number of meshlets in the graph.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the meshlets sharing edges with `meshlet`, ordered by meshlet index.
+    #[inline]
+    pub fn neighbors(&self, meshlet: usize) -> &[MeshletNeighbor] {
+        &self.neighbors[self.offsets[meshlet]..self.offsets[meshlet + 1]]
+    }
+}
+
+/// Maps every vertex to the first vertex with the same position.
+pub(crate) fn position_remap(positions: &[[f32; 3]]) -> Vec<u32> {
+    let mut first: HashMap<[u32; 3], u32> = HashMap::with_capacity(positions.len());
+    positions
+        .iter()
+        .enumerate()
+        .map(|(vertex, position)| {
+            *first
+                .entry(position.map(f32::to_bits))
+                .or_insert(vertex as u32)
+        })
+        .collect()
+}
+
+fn distance_squared(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let d = sub(a, b);
+    dot(d, d)
+}
+
+/// Partitions clusters into groups of connected clusters, see `Meshlets::partition`.
+pub(crate) fn partition_clusters(
+    adjacency: &MeshletAdjacency,
+    centers: &[[f32; 3]],
+    group_size: usize,
+) -> Vec<Vec<usize>> {
+    let cluster_count = adjacency.len();
+    let remap = spatial_sort_remap_decoder(centers);
+    let mut order: Vec<usize> = vec![0; cluster_count];
+    for (cluster, &position) in remap.iter().enumerate() {
+        order[position as usize] = cluster;
+    }
+
+    let mut group_of: Vec<usize> = vec![usize::MAX; cluster_count];
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for &seed in &order {
+        if group_of[seed] != usize::MAX {
+            continue;
+        }
+        let group_index = groups.len();
+        group_of[seed] = group_index;
+        let mut group = vec![seed];
+        let mut center = centers[seed];
+        let mut candidates: HashMap<usize, u32> = HashMap::new();
+
+        while group.len() < group_size {
+            for neighbor in adjacency.neighbors(group[group.len() - 1]) {
+                if group_of[neighbor.meshlet] == usize::MAX {
+                    *candidates.entry(neighbor.meshlet).or_insert(0) += neighbor.shared_edges;
+                }
+            }
+
+            // prefer the neighbor sharing the most edges, then the one closest to the group
+            let best = candidates
+                .iter()
+                .max_by(|a, b| {
+                    a.1.cmp(b.1)
+                        .then_with(|| {
+                            distance_squared(centers[*b.0], center)
+                                .partial_cmp(&distance_squared(centers[*a.0], center))
+                                .unwrap_or(Ordering::Equal)
+                        })
+                        .then_with(|| b.0.cmp(a.0))
+                })
+                .map(|(&cluster, _)| cluster);
+            let cluster = match best {
+                Some(cluster) => cluster,
+                None => break,
+            };
+
+            candidates.remove(&cluster);
+            group_of[cluster] = group_index;
+            group.push(cluster);
+            let weight = 1.0 / group.len() as f32;
+            for i in 0..3 {
+                center[i] += (centers[cluster][i] - center[i]) * weight;
+            }
+        }
+        groups.push(group);
+    }
+
+    // merge groups that are less than half full into the adjacent group sharing the most edges
+    for small in 0..groups.len() {
+        let size = groups[small].len();
+        if size == 0 || size * 2 >= group_size {
+            continue;
+        }
+        let mut shared: HashMap<usize, u32> = HashMap::new();
+        for &cluster in &groups[small] {
+            for neighbor in adjacency.neighbors(cluster) {
+                let other = group_of[neighbor.meshlet];
+                if other != small && groups[other].len() + size <= group_size + group_size / 2 {
+                    *shared.entry(other).or_insert(0) += neighbor.shared_edges;
+                }
+            }
+        }
+        if let Some((&target, _)) = shared
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        {
+            let members = std::mem::take(&mut groups[small]);
+            for &cluster in &members {
+                group_of[cluster] = target;
+            }
+            groups[target].extend(members);
+        }
+    }
+    groups.retain(|group| !group.is_empty());
+    groups
+}
+
+/// Layout of the micro index buffer produced by `Meshlets::pack`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeshletTriangleFormat {
+    /// Three `u8` indices per triangle, with the triangles of each meshlet starting at a
+    /// 4-byte boundary; `triangle_offset` is in bytes.
+    Bytes,
+    /// One `u32` word per triangle with the indices packed as `a | b << 8 | c << 16`;
+    /// `triangle_offset` is in words.
+    PackedU32,
+}
+
+/// Meshlets packed into tightly sized buffers, ready for upload to the GPU.
+#[derive(Debug, Clone)]
+pub struct PackedMeshlets {
+    /// Meshlet descriptors, with offsets into `vertices` and `triangles`.
+    pub descriptors: Vec<ffi::meshopt_Meshlet>,
+    /// Vertex indices referencing the original vertex buffer.
+    pub vertices: Vec<u32>,
+    /// Micro index buffer as 4-byte words, laid out according to `format`.
+    pub triangles: Vec<u32>,
+    pub format: MeshletTriangleFormat,
+}
+
+impl PackedMeshlets {
+    /// Returns the meshlet descriptors as bytes, 16 bytes per meshlet.
+    #[inline]
+    pub fn descriptor_bytes(&self) -> &[u8] {
+        typed_to_bytes(&self.descriptors)
+    }
+
+    /// Returns the vertex index buffer as bytes, 4 bytes per vertex.
+    #[inline]
+    pub fn vertex_bytes(&self) -> &[u8] {
+        typed_to_bytes(&self.vertices)
+    }
+
+    /// Returns the primitive index buffer as bytes.
+    #[inline]
+    pub fn triangle_bytes(&self) -> &[u8] {
+        typed_to_bytes(&self.triangles)
+    }
+}
+
+fn validate_meshlet_input(
+    indices: &[u32],
+    vertex_count: usize,
+    max_vertices: usize,
+    max_triangles: usize,
+) -> Result<()> {
+    if !(3..=MESHLET_MAX_VERTICES).contains(&max_vertices) {
+        return Err(Error::Cluster(format!(
+            "max_vertices ({}) must be in range [3..{}]",
+            max_vertices, MESHLET_MAX_VERTICES
+        )));
+    }
+    if !(4..=MESHLET_MAX_TRIANGLES).contains(&max_triangles) || !max_triangles.is_multiple_of(4) {
+        return Err(Error::Cluster(format!(
+            "max_triangles ({}) must be in range [4..{}] and divisible by 4",
+            max_triangles, MESHLET_MAX_TRIANGLES
+        )));
+    }
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::Cluster(format!(
+            "index count ({}) must be a multiple of 3",
+            indices.len()
+        )));
+    }
+    if let Some((position, index)) = indices
+        .iter()
+        .enumerate()
+        .find(|(_, &index)| index as usize >= vertex_count)
+    {
+        return Err(Error::Cluster(format!(
+            "index ({}) at position {} must be less than vertex count ({})",
+            index, position, vertex_count
+        )));
+    }
+    Ok(())
 }
 
 /// Splits the mesh into a set of meshlets where each meshlet has a micro index buffer
@@ -51,14 +697,22 @@ impl Meshlets {
 /// For maximum efficiency the index buffer being converted has to be optimized for vertex
 /// cache first.
 ///
-/// Note: `max_vertices` must be <= 64 and `max_triangles` must be <= 126
+/// `max_vertices` must be in [3..255], and `max_triangles` must be in [4..512] and divisible by 4.
 pub fn build_meshlets(
     indices: &[u32],
     vertices: &VertexDataAdapter<'_>,
     max_vertices: usize,
     max_triangles: usize,
     cone_weight: f32,
-) -> Meshlets {
+) -> Result<Meshlets> {
+    validate_meshlet_input(indices, vertices.vertex_count, max_vertices, max_triangles)?;
+    if !(0.0..=1.0).contains(&cone_weight) {
+        return Err(Error::Cluster(format!(
+            "cone_weight ({}) must be in range [0..1]",
+            cone_weight
+        )));
+    }
+
     let meshlet_count =
         unsafe { ffi::meshopt_buildMeshletsBound(indices.len(), max_vertices, max_triangles) };
     let mut meshlets: Vec<ffi::meshopt_Meshlet> =
@@ -84,11 +738,58 @@ pub fn build_meshlets(
     };
     meshlets.resize(count, unsafe { ::std::mem::zeroed() });
 
-    Meshlets {
+    Ok(Meshlets {
         meshlets,
         vertices: meshlet_verts,
         triangles: meshlet_tris,
-    }
+    })
+}
+
+/// Splits the mesh into a set of meshlets like `build_meshlets`, scanning the index buffer
+/// in order without using vertex positions.
+///
+/// This is useful when positions are not final at build time (e.g. for skinned or morphed
+/// meshes), at the cost of less spatially coherent meshlets and no cone-culling optimization.
+///
+/// For maximum efficiency the index buffer being converted has to be optimized for vertex
+/// cache first.
+///
+/// `max_vertices` must be in [3..255], and `max_triangles` must be in [4..512] and divisible by 4.
+pub fn build_meshlets_scan(
+    indices: &[u32],
+    vertex_count: usize,
+    max_vertices: usize,
+    max_triangles: usize,
+) -> Result<Meshlets> {
+    validate_meshlet_input(indices, vertex_count, max_vertices, max_triangles)?;
+
+    let meshlet_count =
+        unsafe { ffi::meshopt_buildMeshletsBound(indices.len(), max_vertices, max_triangles) };
+    let mut meshlets: Vec<ffi::meshopt_Meshlet> =
+        vec![unsafe { ::std::mem::zeroed() }; meshlet_count];
+
+    let mut meshlet_verts: Vec<u32> = vec![0; meshlet_count * max_vertices];
+    let mut meshlet_tris: Vec<u8> = vec![0; meshlet_count * max_triangles * 3];
+
+    let count = unsafe {
+        ffi::meshopt_buildMeshletsScan(
+            meshlets.as_mut_ptr(),
+            meshlet_verts.as_mut_ptr(),
+            meshlet_tris.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+            max_vertices,
+            max_triangles,
+        )
+    };
+    meshlets.resize(count, unsafe { ::std::mem::zeroed() });
+
+    Ok(Meshlets {
+        meshlets,
+        vertices: meshlet_verts,
+        triangles: meshlet_tris,
+    })
 }
 
 /// Creates bounding volumes that can be used for frustum, backface and occlusion culling.
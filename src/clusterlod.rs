@@ -0,0 +1,415 @@
+use crate::clusterize::{partition_clusters, position_remap};
+use crate::{
+    build_meshlets, compute_meshlet_bounds, positions_adapter, simplify::simplify_locked,
+    simplify_scale, Error, MeshletAdjacency, Meshlets, Result, VertexDataAdapter,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Groups that can't be reduced below this fraction of their triangles are not simplified.
+const MIN_GROUP_REDUCTION: f32 = 0.85;
+
+/// Bounding sphere and simplification error of a cluster or a group of clusters.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct LodBounds {
+    pub center: [f32; 3],
+    pub radius: f32,
+    /// Absolute simplification error, in the units of the vertex positions.
+    pub error: f32,
+}
+
+impl LodBounds {
+    /// Returns the smallest sphere containing both spheres, with the larger of both errors.
+    pub fn merge(&self, other: &LodBounds) -> LodBounds {
+        let error = self.error.max(other.error);
+        let d = [
+            other.center[0] - self.center[0],
+            other.center[1] - self.center[1],
+            other.center[2] - self.center[2],
+        ];
+        let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+
+        if distance + other.radius <= self.radius {
+            LodBounds { error, ..*self }
+        } else if distance + self.radius <= other.radius {
+            LodBounds { error, ..*other }
+        } else {
+            let radius = (distance + self.radius + other.radius) * 0.5;
+            let t = (radius - self.radius) / distance;
+            LodBounds {
+                center: [
+                    self.center[0] + d[0] * t,
+                    self.center[1] + d[1] * t,
+                    self.center[2] + d[2] * t,
+                ],
+                radius,
+                error,
+            }
+        }
+    }
+}
+
+/// A cluster of a `ClusterLods` hierarchy; its geometry is the meshlet with the same index.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LodCluster {
+    /// Level in the hierarchy, 0 for the clusters of the source mesh.
+    pub level: u32,
+    /// Bounds and error of the geometry this cluster represents.
+    pub bounds: LodBounds,
+    /// Bounds and error of the simplified clusters replacing this cluster; the error is
+    /// `f32::MAX` if the cluster isn't simplified any further.
+    pub parent_bounds: LodBounds,
+    /// Group this cluster was simplified with, if any.
+    pub group: Option<usize>,
+}
+
+/// A group of clusters that was simplified and split into new clusters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LodGroup {
+    /// Level of the clusters in this group.
+    pub level: u32,
+    /// Bounds of the group, containing the bounds of all its clusters.
+    pub bounds: LodBounds,
+    /// Clusters that were simplified together.
+    pub children: Vec<usize>,
+    /// Range of the clusters produced from the simplified group.
+    pub clusters: Range<usize>,
+}
+
+/// Hierarchy of clusters produced by `ClusterDag::build`.
+///
+/// To render the mesh at a given quality, select every cluster whose `bounds` error is
+/// acceptable but whose `parent_bounds` error isn't, after projecting both errors to the
+/// screen using the respective spheres. As errors and spheres grow monotonically towards the
+/// roots, this yields a crack free cut through the hierarchy, and each cluster can be tested
+/// independently.
+#[derive(Debug)]
+pub struct ClusterLods {
+    /// Geometry of all clusters, indexing into the source vertex buffer.
+    pub meshlets: Meshlets,
+    pub clusters: Vec<LodCluster>,
+    pub groups: Vec<LodGroup>,
+}
+
+impl ClusterLods {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Returns the number of levels in the hierarchy.
+    pub fn level_count(&self) -> usize {
+        self.clusters
+            .iter()
+            .map(|cluster| cluster.level as usize + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Builds a hierarchy of meshlets for continuous level of detail, as used by virtualized
+/// geometry renderers.
+///
+/// The source mesh is split into meshlets, which are then repeatedly partitioned into groups
+/// of adjacent meshlets like `Meshlets::partition` does. Every group is simplified with the
+/// vertices it shares with other groups locked, so that neighboring groups still match, and
+/// then split into new meshlets that form the next level. This continues until a single
+/// meshlet remains or no group can be simplified any further.
+#[derive(Debug, Clone)]
+pub struct ClusterDag {
+    max_vertices: usize,
+    max_triangles: usize,
+    cone_weight: f32,
+    group_size: usize,
+    ratio: f32,
+    error: f32,
+}
+
+impl ClusterDag {
+    /// Creates a builder for meshlets with at most `max_vertices` vertices and `max_triangles`
+    /// triangles, with the same limits as `build_meshlets`.
+    pub fn new(max_vertices: usize, max_triangles: usize) -> Self {
+        Self {
+            max_vertices,
+            max_triangles,
+            cone_weight: 0.0,
+            group_size: 4,
+            ratio: 0.5,
+            error: 1.0,
+        }
+    }
+
+    /// Sets the cone weight passed to `build_meshlets` (0 by default).
+    pub fn cone_weight(mut self, cone_weight: f32) -> Self {
+        self.cone_weight = cone_weight;
+        self
+    }
+
+    /// Sets the number of meshlets that are simplified together (4 by default).
+    pub fn group_size(mut self, group_size: usize) -> Self {
+        self.group_size = group_size;
+        self
+    }
+
+    /// Sets the fraction of triangles each group is simplified to (0.5 by default).
+    pub fn ratio(mut self, ratio: f32) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Sets the maximum error a single simplification of a group may add, relative to the
+    /// extents of the group (1.0 by default, which only limits the error by `ratio`).
+    ///
+    /// Groups that can't be simplified enough within this error are kept as they are.
+    pub fn error(mut self, error: f32) -> Self {
+        self.error = error;
+        self
+    }
+
+    /// Builds the hierarchy for the triangle list `indices`.
+    pub fn build(&self, indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Result<ClusterLods> {
+        if self.group_size < 2 {
+            return Err(Error::Config(format!(
+                "group size ({}) must be at least 2",
+                self.group_size
+            )));
+        }
+        if !(self.ratio > 0.0 && self.ratio < 1.0) {
+            return Err(Error::Config(format!(
+                "ratio ({}) must be in range (0..1)",
+                self.ratio
+            )));
+        }
+        if self.error.is_nan() || self.error < 0.0 {
+            return Err(Error::Config(format!(
+                "error ({}) must not be negative",
+                self.error
+            )));
+        }
+
+        let base = build_meshlets(
+            indices,
+            vertices,
+            self.max_vertices,
+            self.max_triangles,
+            self.cone_weight,
+        )?;
+        let positions = vertices.positions();
+        let position_remap = position_remap(&positions);
+
+        let mut lods = ClusterLods {
+            meshlets: Meshlets::default(),
+            clusters: Vec::with_capacity(base.len()),
+            groups: Vec::new(),
+        };
+        // triangle lists of all clusters, in source vertex indices
+        let mut cluster_indices: Vec<Vec<u32>> = Vec::with_capacity(base.len());
+        for meshlet in base.iter() {
+            let bounds = compute_meshlet_bounds(meshlet, vertices);
+            lods.meshlets.push(meshlet.vertices, meshlet.triangles);
+            lods.clusters.push(LodCluster {
+                level: 0,
+                bounds: LodBounds {
+                    center: bounds.center,
+                    radius: bounds.radius,
+                    error: 0.0,
+                },
+                parent_bounds: LodBounds {
+                    error: f32::MAX,
+                    ..Default::default()
+                },
+                group: None,
+            });
+            cluster_indices.push(
+                meshlet
+                    .triangles
+                    .iter()
+                    .map(|&index| meshlet.vertices[index as usize])
+                    .collect(),
+            );
+        }
+
+        // vertices of clusters that are no longer simplified stay locked for good
+        let mut retired = vec![false; vertices.vertex_count];
+        let mut pending: Vec<usize> = (0..lods.clusters.len()).collect();
+        let mut level = 0;
+
+        while pending.len() > 1 {
+            let adjacency = MeshletAdjacency::from_clusters(
+                &pending
+                    .iter()
+                    .map(|&cluster| &cluster_indices[cluster][..])
+                    .collect::<Vec<_>>(),
+                &position_remap,
+            );
+            let centers = pending
+                .iter()
+                .map(|&cluster| lods.clusters[cluster].bounds.center)
+                .collect::<Vec<[f32; 3]>>();
+            let groups = partition_clusters(&adjacency, &centers, self.group_size);
+
+            // lock all vertices that are shared between groups
+            let mut owner = vec![usize::MAX; vertices.vertex_count];
+            let mut lock = retired.clone();
+            for (group_index, group) in groups.iter().enumerate() {
+                for &member in group {
+                    for &index in &cluster_indices[pending[member]] {
+                        let vertex = position_remap[index as usize] as usize;
+                        if owner[vertex] == usize::MAX {
+                            owner[vertex] = group_index;
+                        } else if owner[vertex] != group_index {
+                            lock[vertex] = true;
+                        }
+                    }
+                }
+            }
+
+            let mut next: Vec<usize> = Vec::new();
+            for group in &groups {
+                let children = group
+                    .iter()
+                    .map(|&member| pending[member])
+                    .collect::<Vec<usize>>();
+                let simplified = self.simplify_group(
+                    &children,
+                    &cluster_indices,
+                    &positions,
+                    &position_remap,
+                    &lock,
+                )?;
+
+                let (error, meshlets) = if let Some(simplified) = simplified {
+                    simplified
+                } else {
+                    for &child in &children {
+                        for &index in &cluster_indices[child] {
+                            retired[position_remap[index as usize] as usize] = true;
+                        }
+                    }
+                    continue;
+                };
+
+                let mut bounds = lods.clusters[children[0]].bounds;
+                for &child in &children[1..] {
+                    bounds = bounds.merge(&lods.clusters[child].bounds);
+                }
+                bounds.error = bounds.error.max(error);
+
+                let group_index = lods.groups.len();
+                for &child in &children {
+                    lods.clusters[child].parent_bounds = bounds;
+                    lods.clusters[child].group = Some(group_index);
+                }
+
+                let first = lods.clusters.len();
+                for meshlet in meshlets.iter() {
+                    lods.meshlets.push(meshlet.vertices, meshlet.triangles);
+                    lods.clusters.push(LodCluster {
+                        level: level + 1,
+                        bounds,
+                        parent_bounds: LodBounds {
+                            error: f32::MAX,
+                            ..Default::default()
+                        },
+                        group: None,
+                    });
+                    cluster_indices.push(
+                        meshlet
+                            .triangles
+                            .iter()
+                            .map(|&index| meshlet.vertices[index as usize])
+                            .collect(),
+                    );
+                    next.push(lods.clusters.len() - 1);
+                }
+
+                lods.groups.push(LodGroup {
+                    level,
+                    bounds,
+                    children,
+                    clusters: first..lods.clusters.len(),
+                });
+            }
+
+            if next.is_empty() {
+                break;
+            }
+            pending = next;
+            level += 1;
+        }
+
+        Ok(lods)
+    }
+
+    /// Simplifies the merged triangles of a group and splits them into meshlets referencing
+    /// the source vertices, or returns `None` if the group can't be simplified enough.
+    fn simplify_group(
+        &self,
+        children: &[usize],
+        cluster_indices: &[Vec<u32>],
+        positions: &[[f32; 3]],
+        position_remap: &[u32],
+        lock: &[bool],
+    ) -> Result<Option<(f32, Meshlets)>> {
+        // work on a compact copy of the group, so that cost doesn't depend on the mesh size
+        let mut local: HashMap<u32, u32> = HashMap::new();
+        let mut group_vertices: Vec<u32> = Vec::new();
+        let mut group_indices: Vec<u32> = Vec::new();
+        for &child in children {
+            for &index in &cluster_indices[child] {
+                let local_index = *local.entry(index).or_insert_with(|| {
+                    group_vertices.push(index);
+                    group_vertices.len() as u32 - 1
+                });
+                group_indices.push(local_index);
+            }
+        }
+        let group_positions = group_vertices
+            .iter()
+            .map(|&vertex| positions[vertex as usize])
+            .collect::<Vec<[f32; 3]>>();
+        let group_lock = group_vertices
+            .iter()
+            .map(|&vertex| lock[position_remap[vertex as usize] as usize])
+            .collect::<Vec<bool>>();
+
+        let group_adapter = positions_adapter(&group_positions);
+
+        let target_count = (group_indices.len() as f32 * self.ratio) as usize / 3 * 3;
+        let (lod, error) = simplify_locked(
+            &group_indices,
+            &group_adapter,
+            &group_lock,
+            target_count,
+            self.error,
+        );
+        if lod.len() as f32 > group_indices.len() as f32 * MIN_GROUP_REDUCTION {
+            return Ok(None);
+        }
+
+        let error = error * simplify_scale(&group_adapter);
+        let local_meshlets = build_meshlets(
+            &lod,
+            &group_adapter,
+            self.max_vertices,
+            self.max_triangles,
+            self.cone_weight,
+        )?;
+
+        let mut meshlets = Meshlets::default();
+        for meshlet in local_meshlets.iter() {
+            let meshlet_vertices = meshlet
+                .vertices
+                .iter()
+                .map(|&vertex| group_vertices[vertex as usize])
+                .collect::<Vec<u32>>();
+            meshlets.push(&meshlet_vertices, meshlet.triangles);
+        }
+        Ok(Some((error, meshlets)))
+    }
+}
@@ -1,5 +1,132 @@
-use crate::{error_or, ffi, utilities::rcp_safe, Result};
+use crate::{error_or, ffi, utilities::rcp_safe, Error, Meshlets, Result};
+use std::collections::HashMap;
 use std::mem;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Highest index codec version understood by the bundled library.
+pub const INDEX_ENCODER_MAX_VERSION: u8 = 1;
+
+/// Highest vertex codec version understood by the bundled library.
+pub const VERTEX_ENCODER_MAX_VERSION: u8 = 0;
+
+const INDEX_HEADER: u8 = 0xe0;
+const INDEX_SEQUENCE_HEADER: u8 = 0xd0;
+const VERTEX_HEADER: u8 = 0xa0;
+
+// The native encoders read their format version from a process-wide global, so setting an
+// explicit version and encoding has to happen under a lock. The lock also holds the version the
+// library is configured with, which is restored afterwards; it's read once, on first use.
+static INDEX_VERSION: Mutex<Option<i32>> = Mutex::new(None);
+static VERTEX_VERSION: Mutex<Option<i32>> = Mutex::new(None);
+
+fn lock_version(lock: &'static Mutex<Option<i32>>) -> MutexGuard<'static, Option<i32>> {
+    lock.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// The native library has no getter for the version globals, so the current version is read
+// back from the header of a tiny encoded buffer.
+fn current_index_version() -> i32 {
+    let indices: [u32; 3] = [0, 1, 2];
+    let mut buffer: Vec<u8> = vec![0; unsafe { ffi::meshopt_encodeIndexBufferBound(3, 3) }];
+    unsafe {
+        ffi::meshopt_encodeIndexBuffer(buffer.as_mut_ptr(), buffer.len(), indices.as_ptr(), 3);
+    }
+    i32::from(buffer[0] & 0x0f)
+}
+
+fn current_vertex_version() -> i32 {
+    let vertex = [0u8; 4];
+    let mut buffer: Vec<u8> = vec![0; unsafe { ffi::meshopt_encodeVertexBufferBound(1, 4) }];
+    unsafe {
+        ffi::meshopt_encodeVertexBuffer(
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            vertex.as_ptr().cast(),
+            1,
+            4,
+        );
+    }
+    i32::from(buffer[0] & 0x0f)
+}
+
+/// Options controlling the data format produced by the index and vertex encoders.
+///
+/// The native encoders take their format version from process-wide settings. An explicit
+/// version is applied for the duration of a single call and the previous setting is restored
+/// afterwards; calls with an explicit version are serialized around that. Calls without one
+/// don't take the lock, so they may pick up an explicit version used by a concurrent call, and
+/// other code calling `meshopt_encodeIndexVersion`/`meshopt_encodeVertexVersion` directly isn't
+/// coordinated with either.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Data format version to encode, or `None` to use the version the library is currently
+    /// configured with (the default).
+    ///
+    /// Version 0 is decodable by all library versions; index version 1 is decodable by
+    /// meshoptimizer 0.14+ and is generally smaller.
+    pub version: Option<u8>,
+}
+
+impl EncodeOptions {
+    pub fn new(version: u8) -> Self {
+        Self {
+            version: Some(version),
+        }
+    }
+
+    fn with_index_version<R>(self, encode: impl FnOnce() -> R) -> Result<R> {
+        let version = match self.version {
+            Some(version) if version > INDEX_ENCODER_MAX_VERSION => {
+                return Err(Error::Config(format!(
+                    "index encoder version ({}) must be <= {}",
+                    version, INDEX_ENCODER_MAX_VERSION
+                )));
+            }
+            version => version,
+        };
+        Ok(match version {
+            Some(version) => {
+                let mut current = lock_version(&INDEX_VERSION);
+                let previous = *current.get_or_insert_with(current_index_version);
+                unsafe { ffi::meshopt_encodeIndexVersion(i32::from(version)) };
+                let result = encode();
+                unsafe { ffi::meshopt_encodeIndexVersion(previous) };
+                result
+            }
+            None => encode(),
+        })
+    }
+
+    fn with_vertex_version<R>(self, encode: impl FnOnce() -> R) -> Result<R> {
+        let version = match self.version {
+            Some(version) if version > VERTEX_ENCODER_MAX_VERSION => {
+                return Err(Error::Config(format!(
+                    "vertex encoder version ({}) must be <= {}",
+                    version, VERTEX_ENCODER_MAX_VERSION
+                )));
+            }
+            version => version,
+        };
+        Ok(match version {
+            Some(version) => {
+                let mut current = lock_version(&VERTEX_VERSION);
+                let previous = *current.get_or_insert_with(current_vertex_version);
+                unsafe { ffi::meshopt_encodeVertexVersion(i32::from(version)) };
+                let result = encode();
+                unsafe { ffi::meshopt_encodeVertexVersion(previous) };
+                result
+            }
+            None => encode(),
+        })
+    }
+}
+
+const fn assert_valid_index_size<T: Sized>() {
+    assert!(
+        mem::size_of::<T>() == 2 || mem::size_of::<T>() == 4,
+        "size of result type must be 2 or 4 bytes wide"
+    );
+}
 
 /// Encodes index data into an array of bytes that is generally much smaller (<1.5 bytes/triangle)
 /// and compresses better (<1 bytes/triangle) compared to original.
@@ -7,16 +134,25 @@ use std::mem;
 /// For maximum efficiency the index buffer being encoded has to be optimized for vertex cache and
 /// vertex fetch first.
 pub fn encode_index_buffer(indices: &[u32], vertex_count: usize) -> Result<Vec<u8>> {
+    encode_index_buffer_with(indices, vertex_count, EncodeOptions::default())
+}
+
+/// Encodes index data like `encode_index_buffer`, using the format version from `options`.
+pub fn encode_index_buffer_with(
+    indices: &[u32],
+    vertex_count: usize,
+    options: EncodeOptions,
+) -> Result<Vec<u8>> {
     let bounds = unsafe { ffi::meshopt_encodeIndexBufferBound(indices.len(), vertex_count) };
     let mut result: Vec<u8> = vec![0; bounds];
-    let size = unsafe {
+    let size = options.with_index_version(|| unsafe {
         ffi::meshopt_encodeIndexBuffer(
             result.as_mut_ptr(),
             result.len(),
             indices.as_ptr(),
             indices.len(),
         )
-    };
+    })?;
     result.resize(size, 0u8);
     Ok(result)
 }
@@ -28,14 +164,7 @@ pub fn decode_index_buffer<T: Clone + Default + Sized>(
     encoded: &[u8],
     index_count: usize,
 ) -> Result<Vec<T>> {
-    const fn assert_valid_size<T: Sized>() {
-        assert!(
-            mem::size_of::<T>() == 2 || mem::size_of::<T>() == 4,
-            "size of result type must be 2 or 4 bytes wide"
-        );
-    }
-
-    assert_valid_size::<T>();
+    assert_valid_index_size::<T>();
 
     let mut result: Vec<T> = vec![Default::default(); index_count];
     let result_code = unsafe {
@@ -51,16 +180,73 @@ pub fn decode_index_buffer<T: Clone + Default + Sized>(
     error_or(result_code, result)
 }
 
+/// Encodes index sequence into an array of bytes that is generally smaller and compresses better
+/// compared to original.
+///
+/// Input index sequence can represent arbitrary topology; for triangle lists `encode_index_buffer`
+/// is likely to be better.
+pub fn encode_index_sequence(indices: &[u32], vertex_count: usize) -> Result<Vec<u8>> {
+    encode_index_sequence_with(indices, vertex_count, EncodeOptions::default())
+}
+
+/// Encodes index sequence like `encode_index_sequence`, using the format version from `options`.
+pub fn encode_index_sequence_with(
+    indices: &[u32],
+    vertex_count: usize,
+    options: EncodeOptions,
+) -> Result<Vec<u8>> {
+    let bounds = unsafe { ffi::meshopt_encodeIndexSequenceBound(indices.len(), vertex_count) };
+    let mut result: Vec<u8> = vec![0; bounds];
+    let size = options.with_index_version(|| unsafe {
+        ffi::meshopt_encodeIndexSequence(
+            result.as_mut_ptr(),
+            result.len(),
+            indices.as_ptr(),
+            indices.len(),
+        )
+    })?;
+    result.resize(size, 0u8);
+    Ok(result)
+}
+
+/// Decodes index data from an array of bytes generated by `encode_index_sequence`.
+/// The decoder is safe to use for untrusted input, but it may produce garbage
+/// data (e.g. out of range indices).
+pub fn decode_index_sequence<T: Clone + Default + Sized>(
+    encoded: &[u8],
+    index_count: usize,
+) -> Result<Vec<T>> {
+    assert_valid_index_size::<T>();
+
+    let mut result: Vec<T> = vec![Default::default(); index_count];
+    let result_code = unsafe {
+        ffi::meshopt_decodeIndexSequence(
+            result.as_mut_ptr().cast(),
+            index_count,
+            mem::size_of::<T>(),
+            encoded.as_ptr(),
+            encoded.len(),
+        )
+    };
+
+    error_or(result_code, result)
+}
+
 /// Encodes vertex data into an array of bytes that is generally smaller and compresses better
 /// compared to original.
 ///
 /// This function works for a single vertex stream; for multiple vertex streams,
 /// call `encode_vertex_buffer` for each stream.
 pub fn encode_vertex_buffer<T>(vertices: &[T]) -> Result<Vec<u8>> {
+    encode_vertex_buffer_with(vertices, EncodeOptions::default())
+}
+
+/// Encodes vertex data like `encode_vertex_buffer`, using the format version from `options`.
+pub fn encode_vertex_buffer_with<T>(vertices: &[T], options: EncodeOptions) -> Result<Vec<u8>> {
     let bounds =
         unsafe { ffi::meshopt_encodeVertexBufferBound(vertices.len(), mem::size_of::<T>()) };
     let mut result: Vec<u8> = vec![0; bounds];
-    let size = unsafe {
+    let size = options.with_vertex_version(|| unsafe {
         ffi::meshopt_encodeVertexBuffer(
             result.as_mut_ptr(),
             result.len(),
@@ -68,7 +254,7 @@ pub fn encode_vertex_buffer<T>(vertices: &[T]) -> Result<Vec<u8>> {
             vertices.len(),
             mem::size_of::<T>(),
         )
-    };
+    })?;
     result.resize(size, 0u8);
     Ok(result)
 }
@@ -93,6 +279,331 @@ pub fn decode_vertex_buffer<T: Clone + Default>(
     error_or(result_code, result)
 }
 
+/// Returns the format version of an index buffer or index sequence produced by
+/// `encode_index_buffer` or `encode_index_sequence`, without decoding it.
+pub fn decode_index_version(encoded: &[u8]) -> Result<u8> {
+    match encoded.first() {
+        Some(header) if header & 0xf0 == INDEX_HEADER || header & 0xf0 == INDEX_SEQUENCE_HEADER => {
+            Ok(header & 0x0f)
+        }
+        Some(header) => Err(Error::Parse(format!(
+            "invalid index codec header ({:#04x})",
+            header
+        ))),
+        None => Err(Error::Parse("encoded index data is empty".to_string())),
+    }
+}
+
+/// Returns the format version of a vertex buffer produced by `encode_vertex_buffer`,
+/// without decoding it.
+pub fn decode_vertex_version(encoded: &[u8]) -> Result<u8> {
+    match encoded.first() {
+        Some(header) if header & 0xf0 == VERTEX_HEADER => Ok(header & 0x0f),
+        Some(header) => Err(Error::Parse(format!(
+            "invalid vertex codec header ({:#04x})",
+            header
+        ))),
+        None => Err(Error::Parse("encoded vertex data is empty".to_string())),
+    }
+}
+
+/// Meshlets with triangles compressed by `encode_meshlet_triangles`.
+///
+/// The triangles of each meshlet are stored as a bitstream, read from little endian 32-bit
+/// words starting with the least significant bit. Each triangle starts with a 2-bit code:
+/// 0, 1 and 2 reuse the edge `(c, b)`, `(a, c)` or `(b, a)` of the previous triangle `(a, b, c)`
+/// and are followed by one vertex, while 3 starts a new triangle and is followed by three
+/// vertices. Each vertex is a 1-bit flag that is set for the next vertex not referenced yet,
+/// or a clear flag followed by the index of an already referenced vertex, using as many bits
+/// as needed to store `vertex_count - 1`.
+#[derive(Debug, Clone)]
+pub struct EncodedMeshlets {
+    /// Meshlet descriptors; `triangle_offset` is the offset of the triangle stream in `data`
+    /// (in bytes, aligned to 4 bytes).
+    pub meshlets: Vec<ffi::meshopt_Meshlet>,
+    /// Vertex indices of all meshlets, reordered by first use in the triangle streams.
+    pub vertices: Vec<u32>,
+    /// Triangle streams of all meshlets.
+    pub data: Vec<u8>,
+}
+
+impl EncodedMeshlets {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.meshlets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.meshlets.is_empty()
+    }
+
+    /// Decodes all meshlets back to 3 vertex indices per triangle.
+    pub fn decode(&self) -> Result<Meshlets> {
+        let mut result = Meshlets::default();
+        for meshlet in &self.meshlets {
+            let vertex_offset = meshlet.vertex_offset as usize;
+            let vertex_count = meshlet.vertex_count as usize;
+            let triangle_offset = meshlet.triangle_offset as usize;
+            if vertex_offset + vertex_count > self.vertices.len()
+                || triangle_offset > self.data.len()
+            {
+                return Err(Error::Parse(
+                    "meshlet offsets are out of bounds".to_string(),
+                ));
+            }
+            let triangles = decode_meshlet_triangles(
+                &self.data[triangle_offset..],
+                vertex_count,
+                meshlet.triangle_count as usize,
+            )?;
+            result.push(
+                &self.vertices[vertex_offset..vertex_offset + vertex_count],
+                &triangles,
+            );
+        }
+        Ok(result)
+    }
+}
+
+const MESHLET_CODE_RESTART: u32 = 3;
+
+/// Number of bits used for explicit vertex indices in a meshlet with `vertex_count` vertices.
+fn meshlet_index_bits(vertex_count: usize) -> u32 {
+    (usize::BITS - vertex_count.saturating_sub(1).leading_zeros()).max(1)
+}
+
+struct BitWriter {
+    words: Vec<u32>,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        for bit in 0..count {
+            if self.bits.is_multiple_of(32) {
+                self.words.push(0);
+            }
+            let word = self.words.len() - 1;
+            self.words[word] |= ((value >> bit) & 1) << (self.bits % 32);
+            self.bits += 1;
+        }
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn read(&mut self, count: u32) -> Result<u32> {
+        let mut value = 0u32;
+        for bit in 0..count {
+            let byte = self
+                .data
+                .get(self.position / 8)
+                .ok_or_else(|| Error::Parse("meshlet triangle data is truncated".to_string()))?;
+            value |= u32::from((byte >> (self.position % 8)) & 1) << bit;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+/// Reorders triangles so that consecutive triangles share edges where possible, rotating each
+/// triangle so that the shared edge comes first; returns the code of each triangle.
+fn meshlet_strip_order(triangles: &[[u8; 3]]) -> Vec<(u32, [u8; 3])> {
+    let mut edges: HashMap<(u8, u8), usize> = HashMap::new();
+    for (triangle, &[a, b, c]) in triangles.iter().enumerate() {
+        for edge in [(a, b), (b, c), (c, a)] {
+            edges.entry(edge).or_insert(triangle);
+        }
+    }
+    let neighbors = |&[a, b, c]: &[u8; 3]| {
+        [(b, a), (c, b), (a, c)]
+            .into_iter()
+            .filter_map(|edge| edges.get(&edge).copied())
+            .collect::<Vec<usize>>()
+    };
+    let mut live = triangles
+        .iter()
+        .map(|triangle| neighbors(triangle).len())
+        .collect::<Vec<usize>>();
+
+    let mut used = vec![false; triangles.len()];
+    let mut result: Vec<(u32, [u8; 3])> = Vec::with_capacity(triangles.len());
+    while result.len() < triangles.len() {
+        let next = result.last().and_then(|&(_, [a, b, c])| {
+            [(c, b), (a, c), (b, a)]
+                .into_iter()
+                .enumerate()
+                .find_map(|(code, edge)| {
+                    edges
+                        .get(&edge)
+                        .filter(|&&triangle| !used[triangle])
+                        .map(|&triangle| (code as u32, triangle, edge))
+                })
+        });
+
+        let (code, index, triangle) = if let Some((code, index, (first, second))) = next {
+            // rotate the triangle so that it starts with the shared edge
+            let [a, b, c] = triangles[index];
+            let rotated = if a == first && b == second {
+                [a, b, c]
+            } else if b == first && c == second {
+                [b, c, a]
+            } else {
+                [c, a, b]
+            };
+            (code, index, rotated)
+        } else {
+            // restart with the triangle that has the fewest unused neighbors left
+            let index = (0..triangles.len())
+                .filter(|&triangle| !used[triangle])
+                .min_by_key(|&triangle| live[triangle])
+                .unwrap_or(0);
+            (MESHLET_CODE_RESTART, index, triangles[index])
+        };
+
+        used[index] = true;
+        for neighbor in neighbors(&triangles[index]) {
+            live[neighbor] = live[neighbor].saturating_sub(1);
+        }
+        result.push((code, triangle));
+    }
+    result
+}
+
+/// Compresses the triangles of all meshlets into compact bitstreams.
+///
+/// Triangles are reordered within each meshlet so that consecutive triangles share edges,
+/// and meshlet vertices are reordered by first use; a triangle that continues the previous
+/// one with a new vertex takes 3 bits. Decode with `EncodedMeshlets::decode` or
+/// `decode_meshlet_triangles`.
+pub fn encode_meshlet_triangles(meshlets: &Meshlets) -> EncodedMeshlets {
+    let mut result = EncodedMeshlets {
+        meshlets: Vec::with_capacity(meshlets.len()),
+        vertices: Vec::with_capacity(meshlets.vertices().len()),
+        data: Vec::new(),
+    };
+
+    for meshlet in meshlets.iter() {
+        let triangles = meshlet
+            .triangles
+            .chunks_exact(3)
+            .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+            .collect::<Vec<[u8; 3]>>();
+        let index_bits = meshlet_index_bits(meshlet.vertices.len());
+
+        let mut writer = BitWriter {
+            words: Vec::new(),
+            bits: 0,
+        };
+        let mut remap = vec![u32::MAX; meshlet.vertices.len()];
+        let mut next_vertex = 0u32;
+        for (code, triangle) in meshlet_strip_order(&triangles) {
+            writer.write(code, 2);
+            let new_vertices = if code == MESHLET_CODE_RESTART {
+                &triangle[..]
+            } else {
+                &triangle[2..]
+            };
+            for &vertex in new_vertices {
+                let vertex = vertex as usize;
+                if remap[vertex] == u32::MAX {
+                    remap[vertex] = next_vertex;
+                    next_vertex += 1;
+                    writer.write(1, 1);
+                } else {
+                    writer.write(0, 1);
+                    writer.write(remap[vertex], index_bits);
+                }
+            }
+        }
+
+        // keep vertices that aren't referenced by any triangle after the referenced ones
+        for vertex in &mut remap {
+            if *vertex == u32::MAX {
+                *vertex = next_vertex;
+                next_vertex += 1;
+            }
+        }
+        let vertex_offset = result.vertices.len();
+        result
+            .vertices
+            .resize(vertex_offset + meshlet.vertices.len(), 0);
+        for (vertex, &target) in remap.iter().enumerate() {
+            result.vertices[vertex_offset + target as usize] = meshlet.vertices[vertex];
+        }
+
+        result.meshlets.push(ffi::meshopt_Meshlet {
+            vertex_offset: vertex_offset as u32,
+            triangle_offset: result.data.len() as u32,
+            vertex_count: meshlet.vertices.len() as u32,
+            triangle_count: triangles.len() as u32,
+        });
+        for word in writer.words {
+            result.data.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    result
+}
+
+/// Decodes the triangle stream of a single meshlet produced by `encode_meshlet_triangles`,
+/// returning 3 vertex indices per triangle.
+///
+/// The decoder only reads the stream sequentially and keeps the previous triangle and the
+/// number of referenced vertices as state, like a shader decoding it would.
+pub fn decode_meshlet_triangles(
+    data: &[u8],
+    vertex_count: usize,
+    triangle_count: usize,
+) -> Result<Vec<u8>> {
+    let index_bits = meshlet_index_bits(vertex_count);
+    let mut reader = BitReader { data, position: 0 };
+    let mut next_vertex = 0u32;
+    let mut read_vertex = |reader: &mut BitReader<'_>| -> Result<u8> {
+        let vertex = if reader.read(1)? == 1 {
+            next_vertex += 1;
+            next_vertex - 1
+        } else {
+            reader.read(index_bits)?
+        };
+        if vertex as usize >= vertex_count {
+            return Err(Error::Parse(format!(
+                "meshlet vertex index ({}) must be less than vertex count ({})",
+                vertex, vertex_count
+            )));
+        }
+        Ok(vertex as u8)
+    };
+
+    let mut result: Vec<u8> = Vec::with_capacity(triangle_count * 3);
+    let mut previous = [0u8; 3];
+    for triangle in 0..triangle_count {
+        let code = reader.read(2)?;
+        if triangle == 0 && code != MESHLET_CODE_RESTART {
+            return Err(Error::Parse(
+                "first meshlet triangle must not reference a previous triangle".to_string(),
+            ));
+        }
+        let [a, b, c] = previous;
+        previous = match code {
+            0 => [c, b, read_vertex(&mut reader)?],
+            1 => [a, c, read_vertex(&mut reader)?],
+            2 => [b, a, read_vertex(&mut reader)?],
+            _ => [
+                read_vertex(&mut reader)?,
+                read_vertex(&mut reader)?,
+                read_vertex(&mut reader)?,
+            ],
+        };
+        result.extend_from_slice(&previous);
+    }
+    Ok(result)
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct EncodeHeader {
@@ -29,6 +29,10 @@ pub enum Error {
     #[error("config error: {0}")]
     Config(String),
 
+    /// An error that occurred due to invalid meshlet limits or input to the meshlet builders.
+    #[error("cluster error: {0}")]
+    Cluster(String),
+
     /// An unexpected I/O error occurred.
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -0,0 +1,163 @@
+use crate::{ffi, Error, Result};
+use std::mem;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for i8 {}
+    impl Sealed for i16 {}
+    impl Sealed for u32 {}
+    impl Sealed for f32 {}
+}
+
+/// Component type of octahedral encoded vectors, implemented for `i8` and `i16`.
+pub trait OctComponent: sealed::Sealed + Copy + Default {
+    /// Number of bits of the component.
+    const BITS: u32;
+}
+
+impl OctComponent for i8 {
+    const BITS: u32 = 8;
+}
+
+impl OctComponent for i16 {
+    const BITS: u32 = 16;
+}
+
+/// Component type of exponential encoded data, implemented for `u32` and `f32`.
+///
+/// Decoding replaces each component with the decoded value, so decoding `f32` components
+/// yields the floats directly, while `u32` components hold their bit patterns.
+pub trait ExpComponent: sealed::Sealed + Copy + Default {}
+
+impl ExpComponent for u32 {}
+
+impl ExpComponent for f32 {}
+
+fn check_bits(bits: u32, min: u32, max: u32) -> Result<()> {
+    if bits < min || bits > max {
+        Err(Error::Config(format!(
+            "bits ({}) must be in range [{}..{}]",
+            bits, min, max
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_components(count: usize) -> Result<()> {
+    // the native filter works on vectors of up to 256 bytes
+    if count == 0 || count > 64 {
+        Err(Error::Config(format!(
+            "exponential filter component count ({}) must be in range [1..64]",
+            count
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Encodes unit vectors with K-bit (K <= 16) signed X/Y as an output, using octahedral encoding.
+///
+/// The `encode_filter_*` functions produce data in the format used by the `EXT_meshopt_compression`
+/// glTF extension; the result should be passed to `encode_vertex_buffer`, and restored in-place with
+/// the matching `decode_filter_*` function after `decode_vertex_buffer`.
+///
+/// Each component is stored as an 8-bit or 16-bit normalized integer depending on `C`; `bits` must
+/// fit in the component. W is preserved as is, which makes this suitable for tangents with a
+/// bitangent sign.
+pub fn encode_filter_oct<C: OctComponent>(data: &[[f32; 4]], bits: u32) -> Result<Vec<[C; 4]>> {
+    check_bits(bits, 1, C::BITS)?;
+    let mut result: Vec<[C; 4]> = vec![[C::default(); 4]; data.len()];
+    unsafe {
+        ffi::meshopt_encodeFilterOct(
+            result.as_mut_ptr().cast(),
+            data.len(),
+            mem::size_of::<[C; 4]>(),
+            bits as i32,
+            data.as_ptr().cast(),
+        );
+    }
+    Ok(result)
+}
+
+/// Encodes unit quaternions with K-bit (4 <= K <= 16) component encoding.
+///
+/// Each component is stored as a 16-bit integer.
+pub fn encode_filter_quat(data: &[[f32; 4]], bits: u32) -> Result<Vec<[i16; 4]>> {
+    check_bits(bits, 4, 16)?;
+    let mut result: Vec<[i16; 4]> = vec![[0; 4]; data.len()];
+    unsafe {
+        ffi::meshopt_encodeFilterQuat(
+            result.as_mut_ptr().cast(),
+            data.len(),
+            mem::size_of::<[i16; 4]>(),
+            bits as i32,
+            data.as_ptr().cast(),
+        );
+    }
+    Ok(result)
+}
+
+/// Encodes arbitrary (finite) floating-point data with 8-bit exponent and K-bit integer
+/// mantissa (1 <= K <= 24).
+///
+/// The exponent is shared between all `N` (1 <= N <= 64) components of a given vector; for
+/// individual (scalar) encoding, use `N = 1`.
+pub fn encode_filter_exp<const N: usize>(data: &[[f32; N]], bits: u32) -> Result<Vec<[u32; N]>> {
+    check_components(N)?;
+    check_bits(bits, 1, 24)?;
+    let mut result: Vec<[u32; N]> = vec![[0u32; N]; data.len()];
+    unsafe {
+        ffi::meshopt_encodeFilterExp(
+            result.as_mut_ptr().cast(),
+            data.len(),
+            mem::size_of::<[u32; N]>(),
+            bits as i32,
+            data.as_ptr().cast(),
+            ffi::meshopt_EncodeExpMode_meshopt_EncodeExpSharedVector,
+        );
+    }
+    Ok(result)
+}
+
+/// Decodes octahedral encoding of a unit vector in-place, as produced by `encode_filter_oct`.
+///
+/// The result is stored as 8-bit or 16-bit normalized integers depending on `C`.
+pub fn decode_filter_oct<C: OctComponent>(data: &mut [[C; 4]]) {
+    unsafe {
+        ffi::meshopt_decodeFilterOct(
+            data.as_mut_ptr().cast(),
+            data.len(),
+            mem::size_of::<[C; 4]>(),
+        );
+    }
+}
+
+/// Decodes quaternion encoding in-place, as produced by `encode_filter_quat`.
+///
+/// The result is stored as 16-bit normalized integers.
+pub fn decode_filter_quat(data: &mut [[i16; 4]]) {
+    unsafe {
+        ffi::meshopt_decodeFilterQuat(
+            data.as_mut_ptr().cast(),
+            data.len(),
+            mem::size_of::<[i16; 4]>(),
+        );
+    }
+}
+
+/// Decodes exponential encoding in-place, as produced by `encode_filter_exp`.
+///
+/// Each component is replaced by the decoded value; see `ExpComponent`.
+pub fn decode_filter_exp<E: ExpComponent, const N: usize>(data: &mut [[E; N]]) -> Result<()> {
+    check_components(N)?;
+    unsafe {
+        ffi::meshopt_decodeFilterExp(
+            data.as_mut_ptr().cast(),
+            data.len(),
+            mem::size_of::<[E; N]>(),
+        );
+    }
+    Ok(())
+}
@@ -84,9 +84,12 @@
 
 pub mod analyze;
 pub mod clusterize;
+pub mod clusterlod;
 pub mod encoding;
 pub mod error;
 pub mod ffi;
+pub mod filters;
+pub mod lod;
 pub mod optimize;
 pub mod packing;
 pub mod remap;
@@ -96,8 +99,8 @@ pub mod stripify;
 pub mod utilities;
 
 pub use crate::{
-    analyze::*, clusterize::*, encoding::*, error::*, optimize::*, packing::*, remap::*, shadow::*,
-    simplify::*, stripify::*, utilities::*,
+    analyze::*, clusterize::*, clusterlod::*, encoding::*, error::*, filters::*, lod::*,
+    optimize::*, packing::*, remap::*, shadow::*, simplify::*, stripify::*, utilities::*,
 };
 use std::marker::PhantomData;
 
@@ -0,0 +1,204 @@
+use crate::{
+    optimize_vertex_cache_in_place, optimize_vertex_fetch_remap, simplify_with_error, Error,
+    Result, VertexDataAdapter,
+};
+use std::ops::Range;
+
+/// Target of a single level of detail in a `LodChain`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LodTarget {
+    /// Fraction of the base triangle count to aim for, in [0..1].
+    pub ratio: f32,
+    /// Maximum error relative to mesh extents that can be tolerated, e.g. 0.01 = 1% deformation.
+    pub error: f32,
+}
+
+/// A single level of detail produced by `LodChain::build`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LodLevel {
+    /// Range of this level inside `Lods::indices`.
+    pub index_range: Range<usize>,
+    /// Resulting error relative to mesh extents; multiply by `simplify_scale` to get the absolute error.
+    pub error: f32,
+}
+
+/// Levels of detail sharing a single index and vertex buffer.
+#[derive(Debug, Clone)]
+pub struct Lods {
+    /// Index buffer containing all levels, coarsest level first.
+    pub indices: Vec<u32>,
+    /// Compacted vertex buffer referenced by `indices`, using the stride of the source vertices.
+    pub vertices: Vec<u8>,
+    /// Space between vertices inside `vertices` (in bytes).
+    pub vertex_stride: usize,
+    /// Levels from the most detailed (the source mesh) to the coarsest.
+    pub levels: Vec<LodLevel>,
+}
+
+impl Lods {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Returns the index buffer of the given level.
+    #[inline]
+    pub fn level_indices(&self, level: usize) -> &[u32] {
+        &self.indices[self.levels[level].index_range.clone()]
+    }
+
+    /// Returns the number of vertices in the compacted vertex buffer.
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len() / self.vertex_stride
+    }
+}
+
+/// Builds a chain of simplified levels of detail for a mesh.
+///
+/// Each level is simplified from the source mesh towards its `LodTarget`, and the chain stops early
+/// once a level can't be reduced any further within its error target or would go below the
+/// triangle floor. Each level is then optimized for vertex cache, and all levels are concatenated
+/// into one index buffer referencing a shared vertex buffer that is optimized for vertex fetch.
+#[derive(Debug, Clone)]
+pub struct LodChain {
+    targets: Vec<LodTarget>,
+    min_triangle_count: usize,
+    optimize_vertex_cache: bool,
+}
+
+impl Default for LodChain {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            min_triangle_count: 0,
+            optimize_vertex_cache: true,
+        }
+    }
+}
+
+impl LodChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a level with an explicit target.
+    pub fn level(mut self, target: LodTarget) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// Adds one level per triangle ratio, each allowed to deviate by at most `max_error`.
+    pub fn ratios(mut self, ratios: &[f32], max_error: f32) -> Self {
+        self.targets.extend(ratios.iter().map(|&ratio| LodTarget {
+            ratio,
+            error: max_error,
+        }));
+        self
+    }
+
+    /// Adds one level per error target, each simplified as far as its error allows.
+    pub fn errors(mut self, errors: &[f32]) -> Self {
+        self.targets
+            .extend(errors.iter().map(|&error| LodTarget { ratio: 0.0, error }));
+        self
+    }
+
+    /// Stops the chain before a level would have fewer than `count` triangles.
+    pub fn min_triangle_count(mut self, count: usize) -> Self {
+        self.min_triangle_count = count;
+        self
+    }
+
+    /// Controls whether each level is optimized with `optimize_vertex_cache` (enabled by default).
+    pub fn optimize_vertex_cache(mut self, optimize: bool) -> Self {
+        self.optimize_vertex_cache = optimize;
+        self
+    }
+
+    /// Builds the levels of detail for the triangle list `indices`.
+    ///
+    /// The first level is always the source mesh.
+    pub fn build(&self, indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Result<Lods> {
+        if !indices.len().is_multiple_of(3) {
+            return Err(Error::memory_dynamic(format!(
+                "index count ({}) must be a multiple of 3",
+                indices.len()
+            )));
+        }
+        for target in &self.targets {
+            if !(0.0..=1.0).contains(&target.ratio) || target.error < 0.0 {
+                return Err(Error::Config(format!(
+                    "invalid LOD target (ratio {}, error {}): ratio must be in [0..1] and error must not be negative",
+                    target.ratio, target.error
+                )));
+            }
+        }
+
+        let mut lods: Vec<(Vec<u32>, f32)> = vec![(indices.to_vec(), 0f32)];
+        for target in &self.targets {
+            let previous_count = lods[lods.len() - 1].0.len();
+            let target_count = (indices.len() as f32 * target.ratio) as usize / 3 * 3;
+            let (lod, error) = simplify_with_error(
+                indices,
+                vertices,
+                target_count.min(previous_count),
+                target.error,
+            );
+            if lod.len() >= previous_count || lod.len() / 3 < self.min_triangle_count {
+                break;
+            }
+            lods.push((lod, error));
+        }
+
+        if self.optimize_vertex_cache {
+            for (lod, _) in &mut lods {
+                optimize_vertex_cache_in_place(lod, vertices.vertex_count);
+            }
+        }
+
+        // concatenate coarse levels first, so that the vertex range referenced by them is as small
+        // as possible after vertex fetch optimization
+        let mut levels: Vec<LodLevel> = Vec::with_capacity(lods.len());
+        let mut offset = lods.iter().map(|(lod, _)| lod.len()).sum::<usize>();
+        let mut lod_indices: Vec<u32> = vec![0; offset];
+        for (lod, error) in &lods {
+            offset -= lod.len();
+            lod_indices[offset..offset + lod.len()].copy_from_slice(lod);
+            levels.push(LodLevel {
+                index_range: offset..offset + lod.len(),
+                error: *error,
+            });
+        }
+
+        let vertex_stride = vertices.vertex_stride;
+        let vertex_data = vertices.reader.get_ref();
+        let remap = optimize_vertex_fetch_remap(&lod_indices, vertices.vertex_count);
+        let vertex_count = remap.iter().filter(|&&target| target != u32::MAX).count();
+
+        let mut compacted: Vec<u8> = vec![0; vertex_count * vertex_stride];
+        for (vertex, &target) in remap.iter().enumerate() {
+            if target != u32::MAX {
+                let src = vertex * vertex_stride;
+                let dst = target as usize * vertex_stride;
+                compacted[dst..dst + vertex_stride]
+                    .copy_from_slice(&vertex_data[src..src + vertex_stride]);
+            }
+        }
+        for index in &mut lod_indices {
+            *index = remap[*index as usize];
+        }
+
+        Ok(Lods {
+            indices: lod_indices,
+            vertices: compacted,
+            vertex_stride,
+            levels,
+        })
+    }
+}
@@ -1,11 +1,15 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter};
+use crate::{
+    ffi, indices_from_u32, indices_to_u32, with_indices_u32_mut, DecodePosition, Index,
+    VertexDataAdapter,
+};
 use std::mem;
 
 /// Reorders indices to reduce the number of GPU vertex shader invocations.
 ///
 /// If index buffer contains multiple ranges for multiple draw calls,
 /// this function needs to be called on each range individually.
-pub fn optimize_vertex_cache(indices: &[u32], vertex_count: usize) -> Vec<u32> {
+pub fn optimize_vertex_cache<I: Index>(indices: &[I], vertex_count: usize) -> Vec<I> {
+    let indices = indices_to_u32(indices);
     let mut optimized: Vec<u32> = vec![0; indices.len()];
     unsafe {
         ffi::meshopt_optimizeVertexCache(
@@ -15,22 +19,57 @@ pub fn optimize_vertex_cache(indices: &[u32], vertex_count: usize) -> Vec<u32> {
             vertex_count,
         );
     }
-    optimized
+    indices_from_u32(optimized)
 }
 
 /// Reorders indices to reduce the number of GPU vertex shader invocations.
 ///
 /// If index buffer contains multiple ranges for multiple draw calls,
 /// this function needs to be called on each range individually.
-pub fn optimize_vertex_cache_in_place(indices: &mut [u32], vertex_count: usize) {
-    unsafe {
+pub fn optimize_vertex_cache_in_place<I: Index>(indices: &mut [I], vertex_count: usize) {
+    with_indices_u32_mut(indices, |indices| unsafe {
         ffi::meshopt_optimizeVertexCache(
             indices.as_mut_ptr(),
             indices.as_ptr(),
             indices.len(),
             vertex_count,
         );
+    });
+}
+
+/// Vertex transform cache optimizer for strip-like caches.
+///
+/// Produces inferior results to `optimize_vertex_cache` from the GPU vertex cache perspective.
+/// However, the resulting index order is more optimal if the goal is to reduce the triangle
+/// strip length or improve compression efficiency.
+pub fn optimize_vertex_cache_strip<I: Index>(indices: &[I], vertex_count: usize) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let mut optimized: Vec<u32> = vec![0; indices.len()];
+    unsafe {
+        ffi::meshopt_optimizeVertexCacheStrip(
+            optimized.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+        );
     }
+    indices_from_u32(optimized)
+}
+
+/// Vertex transform cache optimizer for strip-like caches (in place).
+///
+/// Produces inferior results to `optimize_vertex_cache` from the GPU vertex cache perspective.
+/// However, the resulting index order is more optimal if the goal is to reduce the triangle
+/// strip length or improve compression efficiency.
+pub fn optimize_vertex_cache_strip_in_place<I: Index>(indices: &mut [I], vertex_count: usize) {
+    with_indices_u32_mut(indices, |indices| unsafe {
+        ffi::meshopt_optimizeVertexCacheStrip(
+            indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertex_count,
+        );
+    });
 }
 
 /// Vertex transform cache optimizer for FIFO caches.
@@ -42,11 +81,12 @@ pub fn optimize_vertex_cache_in_place(indices: &mut [u32], vertex_count: usize)
 ///
 /// If index buffer contains multiple ranges for multiple draw calls,
 /// this function needs to be called on each range individually.
-pub fn optimize_vertex_cache_fifo(
-    indices: &[u32],
+pub fn optimize_vertex_cache_fifo<I: Index>(
+    indices: &[I],
     vertex_count: usize,
     cache_size: u32,
-) -> Vec<u32> {
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
     let mut optimized: Vec<u32> = vec![0; indices.len()];
     unsafe {
         ffi::meshopt_optimizeVertexCacheFifo(
@@ -57,7 +97,7 @@ pub fn optimize_vertex_cache_fifo(
             cache_size,
         );
     }
-    optimized
+    indices_from_u32(optimized)
 }
 
 /// Vertex transform cache optimizer for FIFO caches (in place).
@@ -69,12 +109,12 @@ pub fn optimize_vertex_cache_fifo(
 ///
 /// If index buffer contains multiple ranges for multiple draw calls,
 /// this function needs to be called on each range individually.
-pub fn optimize_vertex_cache_fifo_in_place(
-    indices: &mut [u32],
+pub fn optimize_vertex_cache_fifo_in_place<I: Index>(
+    indices: &mut [I],
     vertex_count: usize,
     cache_size: u32,
 ) {
-    unsafe {
+    with_indices_u32_mut(indices, |indices| unsafe {
         ffi::meshopt_optimizeVertexCacheFifo(
             indices.as_mut_ptr(),
             indices.as_ptr(),
@@ -82,7 +122,7 @@ pub fn optimize_vertex_cache_fifo_in_place(
             vertex_count,
             cache_size,
         );
-    }
+    });
 }
 
 /// Reorders vertices and changes indices to reduce the amount of GPU
@@ -92,9 +132,12 @@ pub fn optimize_vertex_cache_fifo_in_place(
 /// use `optimize_vertex_fetch_remap` + `remap_vertex_buffer` for each stream.
 ///
 /// `indices` is used both as an input and as an output index buffer.
-pub fn optimize_vertex_fetch<T: Clone + Default>(indices: &mut [u32], vertices: &[T]) -> Vec<T> {
+pub fn optimize_vertex_fetch<T: Clone + Default>(
+    indices: &mut [impl Index],
+    vertices: &[T],
+) -> Vec<T> {
     let mut result: Vec<T> = vec![T::default(); vertices.len()];
-    let next_vertex = unsafe {
+    let next_vertex = with_indices_u32_mut(indices, |indices| unsafe {
         ffi::meshopt_optimizeVertexFetch(
             result.as_mut_ptr().cast(),
             indices.as_mut_ptr(),
@@ -103,7 +146,7 @@ pub fn optimize_vertex_fetch<T: Clone + Default>(indices: &mut [u32], vertices:
             vertices.len(),
             mem::size_of::<T>(),
         )
-    };
+    });
     result.resize(next_vertex, T::default());
     result
 }
@@ -116,8 +159,8 @@ pub fn optimize_vertex_fetch<T: Clone + Default>(indices: &mut [u32], vertices:
 /// use `optimize_vertex_fetch_remap` + `remap_vertex_buffer` for each stream.
 ///
 /// `indices` and `vertices` are used both as an input and as an output buffer.
-pub fn optimize_vertex_fetch_in_place<T>(indices: &mut [u32], vertices: &mut [T]) -> usize {
-    unsafe {
+pub fn optimize_vertex_fetch_in_place<T>(indices: &mut [impl Index], vertices: &mut [T]) -> usize {
+    with_indices_u32_mut(indices, |indices| unsafe {
         ffi::meshopt_optimizeVertexFetch(
             vertices.as_mut_ptr().cast(),
             indices.as_mut_ptr(),
@@ -126,7 +169,7 @@ pub fn optimize_vertex_fetch_in_place<T>(indices: &mut [u32], vertices: &mut [T]
             vertices.len(),
             mem::size_of::<T>(),
         )
-    }
+    })
 }
 
 /// Generates vertex remap to reduce the amount of GPU memory fetches during
@@ -134,17 +177,20 @@ pub fn optimize_vertex_fetch_in_place<T>(indices: &mut [u32], vertices: &mut [T]
 ///
 /// The resulting remap table should be used to reorder vertex/index buffers
 /// using `optimize_remap_vertex_buffer`/`optimize_remap_index_buffer`.
-pub fn optimize_vertex_fetch_remap(indices: &[u32], vertex_count: usize) -> Vec<u32> {
+///
+/// The table has an entry for each of the `vertex_count` vertices; vertices that aren't
+/// referenced by `indices` map to `u32::MAX`.
+pub fn optimize_vertex_fetch_remap<I: Index>(indices: &[I], vertex_count: usize) -> Vec<u32> {
+    let indices = indices_to_u32(indices);
     let mut result: Vec<u32> = vec![0; vertex_count];
-    let next_vertex = unsafe {
+    unsafe {
         ffi::meshopt_optimizeVertexFetchRemap(
             result.as_mut_ptr(),
             indices.as_ptr(),
             indices.len(),
             vertex_count,
-        )
-    };
-    result.resize(next_vertex, 0u32);
+        );
+    }
     result
 }
 
@@ -156,15 +202,15 @@ pub fn optimize_vertex_fetch_remap(indices: &[u32], vertex_count: usize) -> Vec<
 ///
 /// `threshold` indicates how much the overdraw optimizer can degrade vertex cache
 /// efficiency (1.05 = up to 5%) to reduce overdraw more efficiently.
-pub fn optimize_overdraw_in_place(
-    indices: &mut [u32],
+pub fn optimize_overdraw_in_place<I: Index>(
+    indices: &mut [I],
     vertices: &VertexDataAdapter<'_>,
     threshold: f32,
 ) {
     let vertex_data = vertices.reader.get_ref();
     let vertex_data = vertex_data.as_ptr().cast::<u8>();
     let positions = unsafe { vertex_data.add(vertices.position_offset) };
-    unsafe {
+    with_indices_u32_mut(indices, |indices| unsafe {
         ffi::meshopt_optimizeOverdraw(
             indices.as_mut_ptr(),
             indices.as_ptr(),
@@ -174,7 +220,7 @@ pub fn optimize_overdraw_in_place(
             vertices.vertex_stride,
             threshold,
         );
-    }
+    });
 }
 
 /// Reorders indices to reduce the number of GPU vertex shader invocations
@@ -186,7 +232,7 @@ pub fn optimize_overdraw_in_place(
 /// `threshold` indicates how much the overdraw optimizer can degrade vertex cache
 /// efficiency (1.05 = up to 5%) to reduce overdraw more efficiently.
 pub fn optimize_overdraw_in_place_decoder<T: DecodePosition>(
-    indices: &mut [u32],
+    indices: &mut [impl Index],
     vertices: &[T],
     threshold: f32,
 ) {
@@ -194,7 +240,7 @@ pub fn optimize_overdraw_in_place_decoder<T: DecodePosition>(
         .iter()
         .map(|vertex| vertex.decode_position())
         .collect::<Vec<[f32; 3]>>();
-    unsafe {
+    with_indices_u32_mut(indices, |indices| unsafe {
         ffi::meshopt_optimizeOverdraw(
             indices.as_mut_ptr(),
             indices.as_ptr(),
@@ -204,5 +250,86 @@ pub fn optimize_overdraw_in_place_decoder<T: DecodePosition>(
             mem::size_of::<f32>() * 3,
             threshold,
         );
+    });
+}
+
+/// Generates a remap table that can be used to reorder points for spatial locality.
+///
+/// Resulting remap table maps old vertices to new vertices and can be used in `remap_vertex_buffer`/`remap_index_buffer`.
+pub fn spatial_sort_remap(vertices: &VertexDataAdapter<'_>) -> Vec<u32> {
+    let mut remap: Vec<u32> = vec![0; vertices.vertex_count];
+    unsafe {
+        ffi::meshopt_spatialSortRemap(
+            remap.as_mut_ptr(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        );
+    }
+    remap
+}
+
+/// Generates a remap table that can be used to reorder points for spatial locality.
+///
+/// Resulting remap table maps old vertices to new vertices and can be used in `remap_vertex_buffer`/`remap_index_buffer`.
+pub fn spatial_sort_remap_decoder<T: DecodePosition>(vertices: &[T]) -> Vec<u32> {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    let mut remap: Vec<u32> = vec![0; positions.len()];
+    unsafe {
+        ffi::meshopt_spatialSortRemap(
+            remap.as_mut_ptr(),
+            positions.as_ptr().cast(),
+            positions.len(),
+            mem::size_of::<f32>() * 3,
+        );
+    }
+    remap
+}
+
+/// Reorders triangles for spatial locality, and generates a new index buffer.
+///
+/// The resulting index buffer can be used with other functions like `optimize_vertex_cache`.
+pub fn spatial_sort_triangles<I: Index>(indices: &[I], vertices: &VertexDataAdapter<'_>) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    unsafe {
+        ffi::meshopt_spatialSortTriangles(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        );
+    }
+    indices_from_u32(result)
+}
+
+/// Reorders triangles for spatial locality, and generates a new index buffer.
+///
+/// The resulting index buffer can be used with other functions like `optimize_vertex_cache`.
+pub fn spatial_sort_triangles_decoder<T: DecodePosition, I: Index>(
+    indices: &[I],
+    vertices: &[T],
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    unsafe {
+        ffi::meshopt_spatialSortTriangles(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            positions.as_ptr().cast(),
+            positions.len(),
+            mem::size_of::<f32>() * 3,
+        );
     }
+    indices_from_u32(result)
 }
@@ -109,6 +109,12 @@ impl DecodePosition for Vertex {
     }
 }
 
+impl DecodePosition for [f32; 3] {
+    fn decode_position(&self) -> [f32; 3] {
+        *self
+    }
+}
+
 pub fn pack_vertices<T: FromVertex + Default + Clone>(input: &[Vertex]) -> Vec<T> {
     let mut vertices: Vec<T> = vec![T::default(); input.len()];
     for i in 0..input.len() {
@@ -1,4 +1,4 @@
-use crate::{ffi, VertexStream};
+use crate::{ffi, indices_to_u32, try_indices_from_u32, Index, VertexStream};
 use std::mem;
 
 /// Generates a vertex remap table from the vertex buffer and an optional index buffer and returns number of unique vertices.
@@ -32,6 +32,17 @@ pub fn generate_vertex_remap<T>(vertices: &[T], indices: Option<&[u32]>) -> (usi
     (vertex_count, remap)
 }
 
+/// Generates a vertex remap table from the vertex buffer and a 16-bit or 32-bit index buffer and
+/// returns number of unique vertices.
+///
+/// Same as `generate_vertex_remap` with `Some(indices)`.
+pub fn generate_vertex_remap_indexed<T>(
+    vertices: &[T],
+    indices: &[impl Index],
+) -> (usize, Vec<u32>) {
+    generate_vertex_remap(vertices, Some(&indices_to_u32(indices)))
+}
+
 /// Generates a vertex remap table from multiple vertex streams and an optional index buffer and returns number of unique vertices.
 ///
 /// As a result, all vertices that are binary equivalent map to the same (new) location, with no gaps in the resulting sequence.
@@ -77,12 +88,31 @@ pub fn generate_vertex_remap_multi(
     (vertex_count, remap)
 }
 
+/// Generates a vertex remap table from multiple vertex streams and a 16-bit or 32-bit index buffer
+/// and returns number of unique vertices.
+///
+/// Same as `generate_vertex_remap_multi` with `Some(indices)`.
+pub fn generate_vertex_remap_multi_indexed(
+    vertex_count: usize,
+    streams: &[VertexStream<'_>],
+    indices: &[impl Index],
+) -> (usize, Vec<u32>) {
+    generate_vertex_remap_multi(vertex_count, streams, Some(&indices_to_u32(indices)))
+}
+
 /// Generate index buffer from the source index buffer and remap table generated by `generate_vertex_remap`.
 ///
 /// `indices` can be `None` if the input is unindexed.
-pub fn remap_index_buffer(indices: Option<&[u32]>, vertex_count: usize, remap: &[u32]) -> Vec<u32> {
+///
+/// Panics if a remapped index doesn't fit in `I`, which can only happen with 16-bit indices.
+pub fn remap_index_buffer<I: Index>(
+    indices: Option<&[I]>,
+    vertex_count: usize,
+    remap: &[u32],
+) -> Vec<I> {
     let mut result: Vec<u32> = Vec::new();
     if let Some(indices) = indices {
+        let indices = indices_to_u32(indices);
         result.resize(indices.len(), 0u32);
         unsafe {
             ffi::meshopt_remapIndexBuffer(
@@ -104,7 +134,7 @@ pub fn remap_index_buffer(indices: Option<&[u32]>, vertex_count: usize, remap: &
         }
     }
 
-    result
+    try_indices_from_u32(result).unwrap_or_else(|error| panic!("{}", error))
 }
 
 /// Generates vertex buffer from the source vertex buffer and remap table generated by `generate_vertex_remap`.
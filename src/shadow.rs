@@ -1,4 +1,6 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter, VertexStream};
+use crate::{
+    ffi, indices_from_u32, indices_to_u32, DecodePosition, Index, VertexDataAdapter, VertexStream,
+};
 
 /// Generate index buffer that can be used for more efficient rendering when only a subset of the vertex
 /// attributes is necessary. All vertices that are binary equivalent (wrt first `vertex_size` bytes) map to
@@ -6,7 +8,11 @@ use crate::{ffi, DecodePosition, VertexDataAdapter, VertexStream};
 ///
 /// This makes it possible to use the index buffer for Z pre-pass or shadowmap rendering, while using
 /// the original index buffer for regular rendering.
-pub fn generate_shadow_indices(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Vec<u32> {
+pub fn generate_shadow_indices<I: Index>(
+    indices: &[I],
+    vertices: &VertexDataAdapter<'_>,
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
     let vertex_data = vertices.reader.get_ref();
     let vertex_data = vertex_data.as_ptr().cast::<u8>();
     let positions = unsafe { vertex_data.add(vertices.position_offset) };
@@ -22,7 +28,7 @@ pub fn generate_shadow_indices(indices: &[u32], vertices: &VertexDataAdapter<'_>
             vertices.vertex_stride,
         );
     }
-    shadow_indices
+    indices_from_u32(shadow_indices)
 }
 
 /// Generate index buffer that can be used for more efficient rendering when only a subset of the vertex
@@ -31,10 +37,11 @@ pub fn generate_shadow_indices(indices: &[u32], vertices: &VertexDataAdapter<'_>
 ///
 /// This makes it possible to use the index buffer for Z pre-pass or shadowmap rendering, while using
 /// the original index buffer for regular rendering.
-pub fn generate_shadow_indices_decoder<T: DecodePosition>(
-    indices: &[u32],
+pub fn generate_shadow_indices_decoder<T: DecodePosition, I: Index>(
+    indices: &[I],
     vertices: &[T],
-) -> Vec<u32> {
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
     let vertices = vertices
         .iter()
         .map(|vertex| vertex.decode_position())
@@ -52,7 +59,129 @@ pub fn generate_shadow_indices_decoder<T: DecodePosition>(
             std::mem::size_of::<f32>() * 3,
         );
     }
-    shadow_indices
+    indices_from_u32(shadow_indices)
+}
+
+/// Generate index buffer that can be used as a geometry shader input with triangle adjacency topology.
+///
+/// Each triangle is converted into a 6-vertex patch with the following layout:
+/// - 0, 2, 4: original triangle vertices
+/// - 1, 3, 5: vertices adjacent to edges 02, 24 and 40
+///
+/// The resulting patch can be rendered with geometry shaders using e.g. `VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST_WITH_ADJACENCY`.
+/// This can be used to implement algorithms like silhouette detection/expansion and other forms of GS-driven rendering.
+pub fn generate_adjacency_indices<I: Index>(
+    indices: &[I],
+    vertices: &VertexDataAdapter<'_>,
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let mut adjacency_indices: Vec<u32> = vec![0; indices.len() * 2];
+    unsafe {
+        ffi::meshopt_generateAdjacencyIndexBuffer(
+            adjacency_indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        );
+    }
+    indices_from_u32(adjacency_indices)
+}
+
+/// Generate index buffer that can be used as a geometry shader input with triangle adjacency topology.
+///
+/// Each triangle is converted into a 6-vertex patch with the following layout:
+/// - 0, 2, 4: original triangle vertices
+/// - 1, 3, 5: vertices adjacent to edges 02, 24 and 40
+///
+/// The resulting patch can be rendered with geometry shaders using e.g. `VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST_WITH_ADJACENCY`.
+/// This can be used to implement algorithms like silhouette detection/expansion and other forms of GS-driven rendering.
+pub fn generate_adjacency_indices_decoder<T: DecodePosition, I: Index>(
+    indices: &[I],
+    vertices: &[T],
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let vertices = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    let mut adjacency_indices: Vec<u32> = vec![0; indices.len() * 2];
+    unsafe {
+        ffi::meshopt_generateAdjacencyIndexBuffer(
+            adjacency_indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.as_ptr().cast(),
+            vertices.len(),
+            std::mem::size_of::<f32>() * 3,
+        );
+    }
+    indices_from_u32(adjacency_indices)
+}
+
+/// Generate index buffer that can be used for PN-AEN tessellation with crack-free displacement.
+///
+/// Each triangle is converted into a 12-vertex patch with the following layout:
+/// - 0, 1, 2: original triangle vertices
+/// - 3, 4: opposing edge for edge 0, 1
+/// - 5, 6: opposing edge for edge 1, 2
+/// - 7, 8: opposing edge for edge 2, 0
+/// - 9, 10, 11: dominant vertices for corners 0, 1, 2
+///
+/// The resulting patch can be rendered with hardware tessellation using PN-AEN and displacement mapping.
+/// See "Tessellation on Any Budget" (GDC 2011) for implementation details.
+pub fn generate_tessellation_indices<I: Index>(
+    indices: &[I],
+    vertices: &VertexDataAdapter<'_>,
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let mut tessellation_indices: Vec<u32> = vec![0; indices.len() * 4];
+    unsafe {
+        ffi::meshopt_generateTessellationIndexBuffer(
+            tessellation_indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        );
+    }
+    indices_from_u32(tessellation_indices)
+}
+
+/// Generate index buffer that can be used for PN-AEN tessellation with crack-free displacement.
+///
+/// Each triangle is converted into a 12-vertex patch with the following layout:
+/// - 0, 1, 2: original triangle vertices
+/// - 3, 4: opposing edge for edge 0, 1
+/// - 5, 6: opposing edge for edge 1, 2
+/// - 7, 8: opposing edge for edge 2, 0
+/// - 9, 10, 11: dominant vertices for corners 0, 1, 2
+///
+/// The resulting patch can be rendered with hardware tessellation using PN-AEN and displacement mapping.
+/// See "Tessellation on Any Budget" (GDC 2011) for implementation details.
+pub fn generate_tessellation_indices_decoder<T: DecodePosition, I: Index>(
+    indices: &[I],
+    vertices: &[T],
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
+    let vertices = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    let mut tessellation_indices: Vec<u32> = vec![0; indices.len() * 4];
+    unsafe {
+        ffi::meshopt_generateTessellationIndexBuffer(
+            tessellation_indices.as_mut_ptr(),
+            indices.as_ptr(),
+            indices.len(),
+            vertices.as_ptr().cast(),
+            vertices.len(),
+            std::mem::size_of::<f32>() * 3,
+        );
+    }
+    indices_from_u32(tessellation_indices)
 }
 
 /// Generate index buffer that can be used for more efficient rendering when only a subset of the vertex
@@ -61,11 +190,12 @@ pub fn generate_shadow_indices_decoder<T: DecodePosition>(
 ///
 /// This makes it possible to use the index buffer for Z pre-pass or shadowmap rendering, while using
 /// the original index buffer for regular rendering.
-pub fn generate_shadow_indices_multi(
-    indices: &[u32],
+pub fn generate_shadow_indices_multi<I: Index>(
+    indices: &[I],
     vertex_count: usize,
     streams: &[VertexStream<'_>],
-) -> Vec<u32> {
+) -> Vec<I> {
+    let indices = indices_to_u32(indices);
     let streams: Vec<ffi::meshopt_Stream> = streams
         .iter()
         .map(|stream| ffi::meshopt_Stream {
@@ -85,5 +215,5 @@ pub fn generate_shadow_indices_multi(
             streams.len(),
         );
     }
-    shadow_indices
+    indices_from_u32(shadow_indices)
 }
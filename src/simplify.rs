@@ -1,6 +1,314 @@
-use crate::{ffi, DecodePosition, VertexDataAdapter};
+use crate::clusterize::position_remap;
+use crate::{
+    ffi, optimize_vertex_cache_in_place, optimize_vertex_fetch, positions_adapter, DecodePosition,
+    Error, Result, VertexDataAdapter,
+};
+use std::collections::HashSet;
 use std::mem;
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum SimplifyTarget {
+    Count(usize),
+    Ratio(f32),
+}
+
+/// Options controlling `simplify_with` and `simplify_decoder_with`.
+///
+/// By default the mesh is simplified as far as a relative error of 1% allows, using the
+/// topology preserving simplifier.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SimplifyOptions {
+    target: SimplifyTarget,
+    target_error: f32,
+    absolute_error: bool,
+    sloppy: bool,
+    lock_border: bool,
+    max_iterations: usize,
+    return_error: bool,
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> Self {
+        Self {
+            target: SimplifyTarget::Count(0),
+            target_error: 1e-2,
+            absolute_error: false,
+            sloppy: false,
+            lock_border: false,
+            max_iterations: 1,
+            return_error: false,
+        }
+    }
+}
+
+impl SimplifyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of indices to aim for.
+    pub fn target_count(mut self, count: usize) -> Self {
+        self.target = SimplifyTarget::Count(count);
+        self
+    }
+
+    /// Sets the fraction of the source index count to aim for, in [0..1].
+    pub fn target_ratio(mut self, ratio: f32) -> Self {
+        self.target = SimplifyTarget::Ratio(ratio);
+        self
+    }
+
+    /// Sets the maximum error that can be tolerated, relative to mesh extents (e.g. 0.01 = 1%
+    /// deformation) unless `absolute_error` is enabled.
+    pub fn target_error(mut self, error: f32) -> Self {
+        self.target_error = error;
+        self
+    }
+
+    /// Controls whether the target and the returned error are in absolute (world space) units
+    /// instead of relative to mesh extents (disabled by default).
+    pub fn absolute_error(mut self, absolute: bool) -> Self {
+        self.absolute_error = absolute;
+        self
+    }
+
+    /// Controls whether `simplify_sloppy` is used, which doesn't preserve topology but is always
+    /// able to reach the target (disabled by default).
+    pub fn sloppy(mut self, sloppy: bool) -> Self {
+        self.sloppy = sloppy;
+        self
+    }
+
+    /// Controls whether the vertices on the border of the mesh, i.e. on edges that are used by a
+    /// single triangle, are kept in place (disabled by default). Can't be combined with `sloppy`.
+    pub fn lock_border(mut self, lock: bool) -> Self {
+        self.lock_border = lock;
+        self
+    }
+
+    /// Sets how many times the simplifier may run on its own result while the target isn't
+    /// reached and the error budget isn't used up (1 by default).
+    pub fn max_iterations(mut self, iterations: usize) -> Self {
+        self.max_iterations = iterations;
+        self
+    }
+
+    /// Controls whether `SimplifyResult::error` is filled in (disabled by default).
+    pub fn return_error(mut self, return_error: bool) -> Self {
+        self.return_error = return_error;
+        self
+    }
+
+    fn validate(&self, indices: &[u32]) -> Result<()> {
+        if !indices.len().is_multiple_of(3) {
+            return Err(Error::memory_dynamic(format!(
+                "index count ({}) must be a multiple of 3",
+                indices.len()
+            )));
+        }
+        if let SimplifyTarget::Ratio(ratio) = self.target {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::Config(format!(
+                    "target ratio ({}) must be in [0..1]",
+                    ratio
+                )));
+            }
+        }
+        if !self.target_error.is_finite() || self.target_error < 0.0 {
+            return Err(Error::Config(format!(
+                "target error ({}) must be finite and not negative",
+                self.target_error
+            )));
+        }
+        if self.max_iterations == 0 {
+            return Err(Error::Config(
+                "max iterations must be at least 1".to_string(),
+            ));
+        }
+        if self.sloppy && self.lock_border {
+            return Err(Error::Config(
+                "sloppy simplification can't lock the border".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn run(&self, indices: &[u32], vertices: &VertexDataAdapter<'_>) -> (Vec<u32>, f32) {
+        let target_count = match self.target {
+            SimplifyTarget::Count(count) => count,
+            SimplifyTarget::Ratio(ratio) => (indices.len() as f32 * ratio) as usize / 3 * 3,
+        };
+        let scale = if self.absolute_error {
+            simplify_scale(vertices)
+        } else {
+            1.0
+        };
+        let target_error = if scale > 0.0 {
+            self.target_error / scale
+        } else {
+            0.0
+        };
+        let options = if self.lock_border {
+            ffi::meshopt_SimplifyLockBorder
+        } else {
+            0
+        };
+
+        // every pass is measured against the result of the previous one, so the errors add up
+        let (mut result, mut result_error) = simplify_native(
+            indices,
+            vertices,
+            target_count,
+            target_error,
+            self.sloppy,
+            options,
+        );
+        let mut progress = result.len() < indices.len();
+        for _ in 1..self.max_iterations {
+            if !progress || result.len() <= target_count || result_error >= target_error {
+                break;
+            }
+            let remaining_error = target_error - result_error;
+            let (lod, error) = simplify_native(
+                &result,
+                vertices,
+                target_count,
+                remaining_error,
+                self.sloppy,
+                options,
+            );
+            progress = lod.len() < result.len();
+            result = lod;
+            result_error += error;
+        }
+        (result, result_error * scale)
+    }
+}
+
+/// Result of `simplify_with` and `simplify_decoder_with`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimplifyResult {
+    /// Index buffer referencing vertices from the original vertex buffer.
+    pub indices: Vec<u32>,
+    /// Resulting error, in the units of the target error; only set with
+    /// `SimplifyOptions::return_error`.
+    pub error: Option<f32>,
+}
+
+fn simplify_native(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_count: usize,
+    target_error: f32,
+    sloppy: bool,
+    options: ::std::os::raw::c_uint,
+) -> (Vec<u32>, f32) {
+    let mut result: Vec<u32> = vec![0; indices.len()];
+    let mut result_error = 0f32;
+    let index_count = unsafe {
+        if sloppy {
+            ffi::meshopt_simplifySloppy(
+                result.as_mut_ptr().cast(),
+                indices.as_ptr().cast(),
+                indices.len(),
+                vertices.pos_ptr(),
+                vertices.vertex_count,
+                vertices.vertex_stride,
+                target_count,
+                target_error,
+                &mut result_error,
+            )
+        } else {
+            ffi::meshopt_simplify(
+                result.as_mut_ptr().cast(),
+                indices.as_ptr().cast(),
+                indices.len(),
+                vertices.pos_ptr(),
+                vertices.vertex_count,
+                vertices.vertex_stride,
+                target_count,
+                target_error,
+                options,
+                &mut result_error,
+            )
+        }
+    };
+    result.resize(index_count, 0u32);
+    (result, result_error)
+}
+
+/// Reduces the number of triangles in the mesh as configured by `options`.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer
+/// using `optimize_vertex_fetch` is recommended.
+pub fn simplify_with(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    options: &SimplifyOptions,
+) -> Result<SimplifyResult> {
+    options.validate(indices)?;
+    let (indices, error) = options.run(indices, vertices);
+    Ok(SimplifyResult {
+        indices,
+        error: options.return_error.then_some(error),
+    })
+}
+
+/// Reduces the number of triangles in the mesh as configured by `options`.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, `simplify_compact` returns a compact vertex
+/// buffer instead.
+pub fn simplify_decoder_with<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+    options: &SimplifyOptions,
+) -> Result<SimplifyResult> {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    simplify_with(indices, &positions_adapter(&positions), options)
+}
+
+/// Reduces the number of triangles in the mesh as configured by `options`, and returns a new
+/// vertex and index buffer that only contain the vertices still in use.
+///
+/// The vertices keep their relative order, unless `optimize` is set, in which case the result
+/// is optimized with `optimize_vertex_cache` and `optimize_vertex_fetch`.
+pub fn simplify_compact<T: DecodePosition + Clone + Default>(
+    indices: &[u32],
+    vertices: &[T],
+    options: &SimplifyOptions,
+    optimize: bool,
+) -> Result<(Vec<T>, Vec<u32>)> {
+    let mut result = simplify_decoder_with(indices, vertices, options)?.indices;
+    if optimize {
+        optimize_vertex_cache_in_place(&mut result, vertices.len());
+        let vertices = optimize_vertex_fetch(&mut result, vertices);
+        return Ok((vertices, result));
+    }
+
+    let mut remap: Vec<u32> = vec![u32::MAX; vertices.len()];
+    for &index in &result {
+        remap[index as usize] = 0;
+    }
+    let mut compacted: Vec<T> = Vec::new();
+    for (vertex, target) in vertices.iter().zip(&mut remap) {
+        if *target != u32::MAX {
+            *target = compacted.len() as u32;
+            compacted.push(vertex.clone());
+        }
+    }
+    for index in &mut result {
+        *index = remap[*index as usize];
+    }
+    Ok((compacted, result))
+}
+
 /// Reduces the number of triangles in the mesh, attempting to preserve mesh
 /// appearance as much as possible.
 ///
@@ -14,25 +322,26 @@ pub fn simplify(
     target_count: usize,
     target_error: f32,
 ) -> Vec<u32> {
-    let vertex_data = vertices.reader.get_ref();
-    let vertex_data = vertex_data.as_ptr().cast::<u8>();
-    let positions = unsafe { vertex_data.add(vertices.position_offset) };
-    let mut result: Vec<u32> = vec![0; indices.len()];
-    let index_count = unsafe {
-        ffi::meshopt_simplify(
-            result.as_mut_ptr().cast(),
-            indices.as_ptr().cast(),
-            indices.len(),
-            positions.cast::<f32>(),
-            vertices.vertex_count,
-            vertices.vertex_stride,
-            target_count,
-            target_error,
-            std::ptr::null_mut(),
-        )
-    };
-    result.resize(index_count, 0u32);
-    result
+    simplify_with_error(indices, vertices, target_count, target_error).0
+}
+
+/// Reduces the number of triangles in the mesh, attempting to preserve mesh
+/// appearance as much as possible, and returns the resulting relative error.
+///
+/// The error is relative to mesh extents; multiply it by `simplify_scale` to get
+/// the absolute (world space) error.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer
+/// using `optimize_vertex_fetch` is recommended.
+pub fn simplify_with_error(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_count: usize,
+    target_error: f32,
+) -> (Vec<u32>, f32) {
+    simplify_native(indices, vertices, target_count, target_error, false, 0)
 }
 
 /// Reduces the number of triangles in the mesh, attempting to preserve mesh
@@ -48,26 +357,35 @@ pub fn simplify_decoder<T: DecodePosition>(
     target_count: usize,
     target_error: f32,
 ) -> Vec<u32> {
+    simplify_decoder_with_error(indices, vertices, target_count, target_error).0
+}
+
+/// Reduces the number of triangles in the mesh, attempting to preserve mesh
+/// appearance as much as possible, and returns the resulting relative error.
+///
+/// The error is relative to mesh extents; multiply it by `simplify_scale_decoder` to get
+/// the absolute (world space) error.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer
+/// using `optimize_vertex_fetch` is recommended.
+pub fn simplify_decoder_with_error<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+    target_count: usize,
+    target_error: f32,
+) -> (Vec<u32>, f32) {
     let positions = vertices
         .iter()
         .map(|vertex| vertex.decode_position())
         .collect::<Vec<[f32; 3]>>();
-    let mut result: Vec<u32> = vec![0; indices.len()];
-    let index_count = unsafe {
-        ffi::meshopt_simplify(
-            result.as_mut_ptr().cast(),
-            indices.as_ptr().cast(),
-            indices.len(),
-            positions.as_ptr().cast(),
-            positions.len(),
-            mem::size_of::<f32>() * 3,
-            target_count,
-            target_error,
-            std::ptr::null_mut(),
-        )
-    };
-    result.resize(index_count, 0u32);
-    result
+    simplify_with_error(
+        indices,
+        &positions_adapter(&positions),
+        target_count,
+        target_error,
+    )
 }
 
 /// Reduces the number of triangles in the mesh, sacrificing mesh appearance for simplification performance.
@@ -83,58 +401,455 @@ pub fn simplify_sloppy(
     target_count: usize,
     target_error: f32,
 ) -> Vec<u32> {
-    let vertex_data = vertices.reader.get_ref();
-    let vertex_data = vertex_data.as_ptr().cast::<u8>();
-    let positions = unsafe { vertex_data.add(vertices.position_offset) };
-    let mut result: Vec<u32> = vec![0; indices.len()];
-    let index_count = unsafe {
-        ffi::meshopt_simplifySloppy(
-            result.as_mut_ptr().cast(),
-            indices.as_ptr().cast(),
-            indices.len(),
-            positions.cast(),
+    simplify_sloppy_with_error(indices, vertices, target_count, target_error).0
+}
+
+/// Reduces the number of triangles in the mesh, sacrificing mesh appearance for simplification performance,
+/// and returns the resulting relative error.
+/// The algorithm doesn't preserve mesh topology but is always able to reach target triangle count.
+///
+/// The error is relative to mesh extents; multiply it by `simplify_scale` to get
+/// the absolute (world space) error.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
+/// is recommended.
+pub fn simplify_sloppy_with_error(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    target_count: usize,
+    target_error: f32,
+) -> (Vec<u32>, f32) {
+    simplify_native(indices, vertices, target_count, target_error, true, 0)
+}
+
+/// Reduces the number of triangles in the mesh, sacrificing mesh appearance for simplification performance.
+/// The algorithm doesn't preserve mesh topology but is always able to reach target triangle count.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
+/// is recommended.
+pub fn simplify_sloppy_decoder<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+    target_count: usize,
+    target_error: f32,
+) -> Vec<u32> {
+    simplify_sloppy_decoder_with_error(indices, vertices, target_count, target_error).0
+}
+
+/// Reduces the number of triangles in the mesh, sacrificing mesh appearance for simplification performance,
+/// and returns the resulting relative error.
+/// The algorithm doesn't preserve mesh topology but is always able to reach target triangle count.
+///
+/// The error is relative to mesh extents; multiply it by `simplify_scale_decoder` to get
+/// the absolute (world space) error.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
+/// is recommended.
+pub fn simplify_sloppy_decoder_with_error<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+    target_count: usize,
+    target_error: f32,
+) -> (Vec<u32>, f32) {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    simplify_sloppy_with_error(
+        indices,
+        &positions_adapter(&positions),
+        target_count,
+        target_error,
+    )
+}
+
+/// Reduces the number of points in the cloud to reach the given target.
+///
+/// The resulting index buffer references vertices from the original vertex buffer.
+///
+/// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
+/// is recommended.
+pub fn simplify_points(vertices: &VertexDataAdapter<'_>, target_vertex_count: usize) -> Vec<u32> {
+    let target_vertex_count = target_vertex_count.min(vertices.vertex_count);
+    let mut result: Vec<u32> = vec![0; target_vertex_count];
+    let vertex_count = unsafe {
+        ffi::meshopt_simplifyPoints(
+            result.as_mut_ptr(),
+            vertices.pos_ptr(),
             vertices.vertex_count,
             vertices.vertex_stride,
-            target_count,
-            target_error,
-            std::ptr::null_mut(),
+            std::ptr::null(),
+            0,
+            0.0,
+            target_vertex_count,
         )
     };
-    result.resize(index_count, 0u32);
+    result.resize(vertex_count, 0u32);
     result
 }
 
-/// Reduces the number of triangles in the mesh, sacrificing mesh appearance for simplification performance.
-/// The algorithm doesn't preserve mesh topology but is always able to reach target triangle count.
+/// Reduces the number of points in the cloud to reach the given target.
 ///
 /// The resulting index buffer references vertices from the original vertex buffer.
 ///
 /// If the original vertex data isn't required, creating a compact vertex buffer using `optimize_vertex_fetch`
 /// is recommended.
-pub fn simplify_sloppy_decoder<T: DecodePosition>(
+pub fn simplify_points_decoder<T: DecodePosition>(
+    vertices: &[T],
+    target_vertex_count: usize,
+) -> Vec<u32> {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    let target_vertex_count = target_vertex_count.min(positions.len());
+    let mut result: Vec<u32> = vec![0; target_vertex_count];
+    let vertex_count = unsafe {
+        ffi::meshopt_simplifyPoints(
+            result.as_mut_ptr(),
+            positions.as_ptr().cast(),
+            positions.len(),
+            mem::size_of::<f32>() * 3,
+            std::ptr::null(),
+            0,
+            0.0,
+            target_vertex_count,
+        )
+    };
+    result.resize(vertex_count, 0u32);
+    result
+}
+
+/// Returns the error scaling factor used by the simplifier to convert between absolute and relative extents.
+///
+/// Absolute error must be *divided* by the scaling factor before passing it to `simplify` as `target_error`.
+/// Relative error returned by `simplify_with_error` must be *multiplied* by the scaling factor to get absolute error.
+pub fn simplify_scale(vertices: &VertexDataAdapter<'_>) -> f32 {
+    unsafe {
+        ffi::meshopt_simplifyScale(
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+        )
+    }
+}
+
+/// Returns the error scaling factor used by the simplifier to convert between absolute and relative extents.
+///
+/// Absolute error must be *divided* by the scaling factor before passing it to `simplify_decoder` as `target_error`.
+/// Relative error returned by `simplify_decoder_with_error` must be *multiplied* by the scaling factor to get absolute error.
+pub fn simplify_scale_decoder<T: DecodePosition>(vertices: &[T]) -> f32 {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    unsafe {
+        ffi::meshopt_simplifyScale(
+            positions.as_ptr().cast(),
+            positions.len(),
+            mem::size_of::<f32>() * 3,
+        )
+    }
+}
+
+/// Reduces the number of triangles in the mesh like `simplify_with_error`, keeping the vertices
+/// flagged in `vertex_lock` in place.
+///
+/// This can be used to simplify parts of a larger mesh independently, e.g. terrain tiles or
+/// streaming cells, by locking the vertices returned by `find_border_vertices` so that the
+/// borders of neighboring parts still match.
+pub fn simplify_with_locks(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    vertex_lock: &[bool],
+    target_count: usize,
+    target_error: f32,
+) -> Result<(Vec<u32>, f32)> {
+    check_vertex_lock(vertex_lock, vertices.vertex_count)?;
+    Ok(simplify_locked(
+        indices,
+        vertices,
+        vertex_lock,
+        target_count,
+        target_error,
+    ))
+}
+
+/// Reduces the number of triangles in the mesh like `simplify_decoder_with_error`, keeping the
+/// vertices flagged in `vertex_lock` in place; see `simplify_with_locks`.
+pub fn simplify_with_locks_decoder<T: DecodePosition>(
     indices: &[u32],
     vertices: &[T],
+    vertex_lock: &[bool],
     target_count: usize,
     target_error: f32,
-) -> Vec<u32> {
+) -> Result<(Vec<u32>, f32)> {
+    check_vertex_lock(vertex_lock, vertices.len())?;
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    Ok(simplify_locked(
+        indices,
+        &positions_adapter(&positions),
+        vertex_lock,
+        target_count,
+        target_error,
+    ))
+}
+
+fn check_vertex_lock(vertex_lock: &[bool], vertex_count: usize) -> Result<()> {
+    if vertex_lock.len() != vertex_count {
+        Err(Error::Config(format!(
+            "vertex lock length ({}) must match vertex count ({})",
+            vertex_lock.len(),
+            vertex_count
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns a mask of the vertices on the border of the mesh, i.e. on edges that are used by
+/// a single triangle, for use with `simplify_with_locks`.
+///
+/// Vertices with the same position are treated as one, so attribute seams aren't reported as
+/// borders.
+pub fn find_border_vertices(indices: &[u32], vertices: &VertexDataAdapter<'_>) -> Vec<bool> {
+    border_vertices(indices, &vertices.positions())
+}
+
+/// Returns a mask of the vertices on the border of the mesh; see `find_border_vertices`.
+pub fn find_border_vertices_decoder<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+) -> Vec<bool> {
+    let positions = vertices
+        .iter()
+        .map(|vertex| vertex.decode_position())
+        .collect::<Vec<[f32; 3]>>();
+    border_vertices(indices, &positions)
+}
+
+pub(crate) fn border_vertices(indices: &[u32], positions: &[[f32; 3]]) -> Vec<bool> {
+    let remap = position_remap(positions);
+    let edges = indices
+        .chunks_exact(3)
+        .flat_map(|triangle| (0..3).map(move |i| (triangle[i], triangle[(i + 1) % 3])))
+        .map(|(a, b)| (remap[a as usize], remap[b as usize]))
+        .collect::<HashSet<(u32, u32)>>();
+
+    let mut border = vec![false; positions.len()];
+    for &(a, b) in &edges {
+        if a != b && !edges.contains(&(b, a)) {
+            border[a as usize] = true;
+            border[b as usize] = true;
+        }
+    }
+    remap
+        .iter()
+        .map(|&vertex| border[vertex as usize])
+        .collect()
+}
+
+/// Maximum number of attribute components supported by `simplify_with_attributes`, summed over
+/// all streams.
+pub const SIMPLIFY_MAX_ATTRIBUTES: usize = 16;
+
+/// Vertex attribute stream considered by `simplify_with_attributes`, e.g. normals or
+/// texture coordinates.
+#[derive(Debug, Copy, Clone)]
+pub struct AttributeStream<'a> {
+    /// Attribute data, starting with the first component of the first vertex.
+    pub data: &'a [f32],
+    /// Space between vertices inside `data` (in floats).
+    pub stride: usize,
+    /// Number of components of the attribute.
+    pub size: usize,
+    /// Weight of the attribute relative to the other attributes and to the positions, which
+    /// are measured relative to mesh extents.
+    pub weight: f32,
+}
+
+impl<'a> AttributeStream<'a> {
+    /// Create an `AttributeStream` for a buffer consisting only of attributes with `N` components.
+    pub fn new<const N: usize>(data: &'a [[f32; N]], weight: f32) -> AttributeStream<'a> {
+        Self::new_with_stride(
+            unsafe { std::slice::from_raw_parts(data.as_ptr().cast(), data.len() * N) },
+            N,
+            N,
+            weight,
+        )
+    }
+
+    /// Create an `AttributeStream` for interleaved data, with `size` components per vertex
+    /// that are `stride` floats apart.
+    pub fn new_with_stride(
+        data: &'a [f32],
+        stride: usize,
+        size: usize,
+        weight: f32,
+    ) -> AttributeStream<'a> {
+        AttributeStream {
+            data,
+            stride,
+            size,
+            weight,
+        }
+    }
+}
+
+/// Reduces the number of triangles in the mesh like `simplify_with_error`, while also
+/// preserving vertex attributes such as normals, texture coordinates or colors.
+///
+/// The weighted attributes are part of the error metric, so collapses that would change the
+/// shading or texturing are avoided just like collapses that would change the shape. The
+/// returned error includes the weighted attribute error.
+///
+/// The streams may have up to `SIMPLIFY_MAX_ATTRIBUTES` components in total.
+pub fn simplify_with_attributes(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    attributes: &[AttributeStream<'_>],
+    target_count: usize,
+    target_error: f32,
+) -> Result<(Vec<u32>, f32)> {
+    let (data, weights) = interleave_attributes(attributes, vertices.vertex_count)?;
+    Ok(simplify_native_attributes(
+        indices,
+        vertices,
+        &data,
+        &weights,
+        None,
+        target_count,
+        target_error,
+    ))
+}
+
+/// Reduces the number of triangles in the mesh like `simplify_decoder_with_error`, while also
+/// preserving vertex attributes; see `simplify_with_attributes`.
+pub fn simplify_with_attributes_decoder<T: DecodePosition>(
+    indices: &[u32],
+    vertices: &[T],
+    attributes: &[AttributeStream<'_>],
+    target_count: usize,
+    target_error: f32,
+) -> Result<(Vec<u32>, f32)> {
     let positions = vertices
         .iter()
         .map(|vertex| vertex.decode_position())
         .collect::<Vec<[f32; 3]>>();
+    simplify_with_attributes(
+        indices,
+        &positions_adapter(&positions),
+        attributes,
+        target_count,
+        target_error,
+    )
+}
+
+/// Packs `attributes` into a single buffer with all components of a vertex next to each other,
+/// as expected by the native simplifier, and returns it along with the weight of each component.
+fn interleave_attributes(
+    attributes: &[AttributeStream<'_>],
+    vertex_count: usize,
+) -> Result<(Vec<f32>, Vec<f32>)> {
+    let mut weights: Vec<f32> = Vec::new();
+    for attribute in attributes {
+        if attribute.size > attribute.stride {
+            return Err(Error::Config(format!(
+                "attribute size ({}) must not be larger than stride ({})",
+                attribute.size, attribute.stride
+            )));
+        }
+        if vertex_count > 0
+            && attribute.data.len() < (vertex_count - 1) * attribute.stride + attribute.size
+        {
+            return Err(Error::Config(format!(
+                "attribute data length ({}) is too short for vertex count ({})",
+                attribute.data.len(),
+                vertex_count
+            )));
+        }
+        weights.resize(weights.len() + attribute.size, attribute.weight);
+    }
+    if weights.len() > SIMPLIFY_MAX_ATTRIBUTES {
+        return Err(Error::Config(format!(
+            "attribute component count ({}) must not exceed {}",
+            weights.len(),
+            SIMPLIFY_MAX_ATTRIBUTES
+        )));
+    }
+
+    let mut data: Vec<f32> = Vec::with_capacity(vertex_count * weights.len());
+    for vertex in 0..vertex_count {
+        for attribute in attributes {
+            let offset = vertex * attribute.stride;
+            data.extend_from_slice(&attribute.data[offset..offset + attribute.size]);
+        }
+    }
+    Ok((data, weights))
+}
+
+/// Calls the native attribute aware simplifier; `attributes` holds `weights.len()` components
+/// per vertex, and `vertex_lock` flags the vertices that can't be moved.
+fn simplify_native_attributes(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    attributes: &[f32],
+    weights: &[f32],
+    vertex_lock: Option<&[bool]>,
+    target_count: usize,
+    target_error: f32,
+) -> (Vec<u32>, f32) {
     let mut result: Vec<u32> = vec![0; indices.len()];
+    let mut result_error = 0f32;
     let index_count = unsafe {
-        ffi::meshopt_simplifySloppy(
-            result.as_mut_ptr().cast(),
-            indices.as_ptr().cast(),
+        ffi::meshopt_simplifyWithAttributes(
+            result.as_mut_ptr(),
+            indices.as_ptr(),
             indices.len(),
-            positions.as_ptr().cast(),
-            positions.len(),
-            mem::size_of::<f32>() * 3,
+            vertices.pos_ptr(),
+            vertices.vertex_count,
+            vertices.vertex_stride,
+            attributes.as_ptr(),
+            mem::size_of_val(weights),
+            weights.as_ptr(),
+            weights.len(),
+            // bool has the same layout as the expected 0 or 1 bytes
+            vertex_lock.map_or(std::ptr::null(), |lock| lock.as_ptr().cast()),
             target_count,
             target_error,
-            std::ptr::null_mut(),
+            0,
+            &mut result_error,
         )
     };
     result.resize(index_count, 0u32);
-    result
+    (result, result_error)
+}
+
+/// Reduces the number of triangles in the mesh like `simplify_with_error`, keeping the vertices
+/// flagged in `lock` in place.
+pub(crate) fn simplify_locked(
+    indices: &[u32],
+    vertices: &VertexDataAdapter<'_>,
+    lock: &[bool],
+    target_count: usize,
+    target_error: f32,
+) -> (Vec<u32>, f32) {
+    simplify_native_attributes(
+        indices,
+        vertices,
+        &[],
+        &[],
+        Some(lock),
+        target_count,
+        target_error,
+    )
 }
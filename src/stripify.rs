@@ -1,4 +1,4 @@
-use crate::{ffi, Error, Result};
+use crate::{ffi, indices_from_u32, indices_to_u32, Error, Index, Result};
 
 /// Converts a previously vertex cache optimized triangle list to triangle
 /// strip, stitching strips using restart index.
@@ -8,7 +8,8 @@ use crate::{ffi, Error, Result};
 ///
 /// The `restart_index` should be 0xffff or 0xffffffff depending on index size,
 /// or 0 to use degenerate triangles.
-pub fn stripify(indices: &[u32], vertex_count: usize, restart_index: u32) -> Result<Vec<u32>> {
+pub fn stripify<I: Index>(indices: &[I], vertex_count: usize, restart_index: I) -> Result<Vec<I>> {
+    let indices = indices_to_u32(indices);
     let mut result: Vec<u32> = vec![0; indices.len() / 3 * 4];
     let index_count = unsafe {
         ffi::meshopt_stripify(
@@ -16,31 +17,32 @@ pub fn stripify(indices: &[u32], vertex_count: usize, restart_index: u32) -> Res
             indices.as_ptr().cast(),
             indices.len(),
             vertex_count,
-            restart_index,
+            restart_index.to_u32(),
         )
     };
     if index_count <= result.len() {
         result.resize(index_count, 0u32);
-        Ok(result)
+        Ok(indices_from_u32(result))
     } else {
         Err(Error::memory("index count is larger than result"))
     }
 }
 
 /// Converts a triangle strip to a triangle list
-pub fn unstripify(indices: &[u32], restart_index: u32) -> Result<Vec<u32>> {
+pub fn unstripify<I: Index>(indices: &[I], restart_index: I) -> Result<Vec<I>> {
+    let indices = indices_to_u32(indices);
     let mut result: Vec<u32> = vec![0; (indices.len() - 2) * 3];
     let index_count = unsafe {
         ffi::meshopt_unstripify(
             result.as_mut_ptr().cast(),
             indices.as_ptr().cast(),
             indices.len(),
-            restart_index,
+            restart_index.to_u32(),
         )
     };
     if index_count <= result.len() {
         result.resize(index_count, 0u32);
-        Ok(result)
+        Ok(indices_from_u32(result))
     } else {
         Err(Error::memory("index count is larger than result"))
     }
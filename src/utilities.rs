@@ -1,5 +1,7 @@
 use crate::{Error, Result};
+use std::borrow::Cow;
 use std::io::{Cursor, Read};
+use std::ops::Range;
 
 #[inline(always)]
 pub fn any_as_u8_slice<T: Sized>(p: &T) -> &[u8] {
@@ -16,25 +18,266 @@ pub fn typed_to_bytes<T: Sized>(typed: &[T]) -> &[u8] {
     }
 }
 
+fn index_16_error(position: usize, index: u32, max: u32) -> Error {
+    Error::memory_dynamic(format!(
+        "index value ({}) at position {} must be <= {} when converting to 16-bit",
+        index, position, max
+    ))
+}
+
+/// Converts 32-bit indices to 16-bit indices.
+///
+/// Fails on the first index that doesn't fit in 16 bits, reporting its position.
 pub fn convert_indices_32_to_16(indices: &[u32]) -> Result<Vec<u16>> {
+    indices
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| {
+            u16::try_from(index)
+                .map_err(|_error| index_16_error(position, index, u32::from(u16::MAX)))
+        })
+        .collect()
+}
+
+/// Converts 32-bit indices to 16-bit indices, mapping the primitive restart value 0xffffffff
+/// to 0xffff.
+///
+/// Fails on the first index that doesn't fit in 16 bits or would be mistaken for the restart
+/// value (0xffff), reporting its position.
+pub fn convert_indices_32_to_16_with_restart(indices: &[u32]) -> Result<Vec<u16>> {
+    indices
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| match index {
+            u32::MAX => Ok(u16::MAX),
+            index if index < u32::from(u16::MAX) => Ok(index as u16),
+            index => Err(index_16_error(position, index, u32::from(u16::MAX) - 1)),
+        })
+        .collect()
+}
+
+/// Converts 16-bit indices to 32-bit indices.
+pub fn convert_indices_16_to_32(indices: &[u16]) -> Vec<u32> {
+    indices.iter().map(|&index| u32::from(index)).collect()
+}
+
+/// Converts 16-bit indices to 32-bit indices, mapping the primitive restart value 0xffff to
+/// 0xffffffff.
+pub fn convert_indices_16_to_32_with_restart(indices: &[u16]) -> Vec<u32> {
+    indices
+        .iter()
+        .map(|&index| match index {
+            u16::MAX => u32::MAX,
+            index => u32::from(index),
+        })
+        .collect()
+}
+
+/// Returns whether `convert_indices_32_to_16` succeeds, i.e. all indices fit in 16 bits.
+pub fn can_convert_indices_32_to_16(indices: &[u32]) -> bool {
+    indices.iter().all(|&index| u16::try_from(index).is_ok())
+}
+
+/// Returns whether `convert_indices_32_to_16_with_restart` succeeds, i.e. all indices are either
+/// the primitive restart value 0xffffffff or smaller than 0xffff, so that none of them is
+/// mistaken for the 16-bit restart value.
+pub fn can_convert_indices_32_to_16_with_restart(indices: &[u32]) -> bool {
+    indices
+        .iter()
+        .all(|&index| index == u32::MAX || index < u32::from(u16::MAX))
+}
+
+/// A draw of a triangle list split by `split_indices_32_to_16`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexDraw16 {
+    /// Range of this draw inside the 16-bit index buffer.
+    pub index_range: Range<usize>,
+    /// Vertex offset of this draw, to be added to every index (e.g. `baseVertex`/`vertexOffset`).
+    pub base_vertex: u32,
+}
+
+/// Converts a triangle list with 32-bit indices to 16-bit indices, splitting it into as few
+/// draws as possible that each reference a range of at most 65535 vertices.
+///
+/// Triangles keep their order, and each draw stores its indices relative to its `base_vertex`,
+/// so that no index becomes the restart value 0xffff. If all indices are smaller than 0xffff, a
+/// single draw is returned.
+pub fn split_indices_32_to_16(indices: &[u32]) -> Result<(Vec<u16>, Vec<IndexDraw16>)> {
+    if !indices.len().is_multiple_of(3) {
+        return Err(Error::memory_dynamic(format!(
+            "index count ({}) must be a multiple of 3",
+            indices.len()
+        )));
+    }
+    let max_range = u32::from(u16::MAX) - 1;
+    if indices.iter().all(|&index| index <= max_range) {
+        let result = indices.iter().map(|&index| index as u16).collect();
+        let draw = IndexDraw16 {
+            index_range: 0..indices.len(),
+            base_vertex: 0,
+        };
+        return Ok((result, vec![draw]));
+    }
+
+    let mut draws: Vec<IndexDraw16> = Vec::new();
+    let mut start = 0;
+    let mut min = u32::MAX;
+    let mut max = 0;
+    for (triangle_index, triangle) in indices.chunks_exact(3).enumerate() {
+        let triangle_min = triangle[0].min(triangle[1]).min(triangle[2]);
+        let triangle_max = triangle[0].max(triangle[1]).max(triangle[2]);
+        if triangle_max - triangle_min > max_range {
+            return Err(Error::memory_dynamic(format!(
+                "triangle at position {} spans more than {} vertices",
+                triangle_index * 3,
+                max_range + 1
+            )));
+        }
+        if triangle_max.max(max) - triangle_min.min(min) > max_range {
+            draws.push(IndexDraw16 {
+                index_range: start..triangle_index * 3,
+                base_vertex: min,
+            });
+            start = triangle_index * 3;
+            min = u32::MAX;
+            max = 0;
+        }
+        min = min.min(triangle_min);
+        max = max.max(triangle_max);
+    }
+    draws.push(IndexDraw16 {
+        index_range: start..indices.len(),
+        base_vertex: min,
+    });
+
     let mut result: Vec<u16> = Vec::with_capacity(indices.len());
-    for index in indices {
-        if *index > 65536 {
-            return Err(Error::memory(
-                "index value must be <= 65536 when converting to 16-bit",
-            ));
+    for draw in &draws {
+        result.extend(
+            indices[draw.index_range.clone()]
+                .iter()
+                .map(|&index| (index - draw.base_vertex) as u16),
+        );
+    }
+    Ok((result, draws))
+}
+
+mod sealed {
+    use std::borrow::Cow;
+
+    pub trait Sealed: Sized {
+        fn slice_to_u32(indices: &[Self]) -> Cow<'_, [u32]>;
+        fn with_u32_mut<R>(indices: &mut [Self], f: impl FnOnce(&mut [u32]) -> R) -> R;
+        fn vec_from_u32(indices: Vec<u32>) -> Vec<Self>;
+        fn try_vec_from_u32(indices: Vec<u32>) -> crate::Result<Vec<Self>>;
+    }
+
+    impl Sealed for u16 {
+        fn slice_to_u32(indices: &[Self]) -> Cow<'_, [u32]> {
+            Cow::Owned(indices.iter().map(|&index| u32::from(index)).collect())
+        }
+
+        fn with_u32_mut<R>(indices: &mut [Self], f: impl FnOnce(&mut [u32]) -> R) -> R {
+            let mut converted = Self::slice_to_u32(indices).into_owned();
+            let result = f(&mut converted);
+            for (index, &converted) in indices.iter_mut().zip(&converted) {
+                debug_assert!(
+                    u16::try_from(converted).is_ok(),
+                    "index ({}) doesn't fit in 16 bits",
+                    converted
+                );
+                *index = converted as u16;
+            }
+            result
+        }
+
+        fn vec_from_u32(indices: Vec<u32>) -> Vec<Self> {
+            indices
+                .into_iter()
+                .map(|index| {
+                    debug_assert!(
+                        u16::try_from(index).is_ok(),
+                        "index ({}) doesn't fit in 16 bits",
+                        index
+                    );
+                    index as u16
+                })
+                .collect()
+        }
+
+        fn try_vec_from_u32(indices: Vec<u32>) -> crate::Result<Vec<Self>> {
+            crate::convert_indices_32_to_16(&indices)
+        }
+    }
+
+    impl Sealed for u32 {
+        fn slice_to_u32(indices: &[Self]) -> Cow<'_, [u32]> {
+            Cow::Borrowed(indices)
+        }
+
+        fn with_u32_mut<R>(indices: &mut [Self], f: impl FnOnce(&mut [u32]) -> R) -> R {
+            f(indices)
+        }
+
+        fn vec_from_u32(indices: Vec<u32>) -> Vec<Self> {
+            indices
+        }
+
+        fn try_vec_from_u32(indices: Vec<u32>) -> crate::Result<Vec<Self>> {
+            Ok(indices)
         }
-        result.push(*index as u16);
     }
-    Ok(result)
 }
 
-pub fn convert_indices_16_to_32(indices: &[u16]) -> Result<Vec<u32>> {
-    let mut result: Vec<u32> = Vec::with_capacity(indices.len());
-    for index in indices {
-        result.push(u32::from(*index));
+/// Index buffer element type, implemented for `u16` and `u32`.
+///
+/// The native library works on 32-bit indices, so 16-bit index buffers are converted on the way
+/// in and out, while 32-bit index buffers are passed through as is.
+pub trait Index: sealed::Sealed + Copy + Default + 'static {
+    /// Converts the index to 32 bits.
+    fn to_u32(self) -> u32;
+}
+
+impl Index for u16 {
+    #[inline]
+    fn to_u32(self) -> u32 {
+        u32::from(self)
+    }
+}
+
+impl Index for u32 {
+    #[inline]
+    fn to_u32(self) -> u32 {
+        self
     }
-    Ok(result)
+}
+
+/// Returns `indices` as 32-bit indices, only copying them if they are 16-bit.
+pub(crate) fn indices_to_u32<I: Index>(indices: &[I]) -> Cow<'_, [u32]> {
+    I::slice_to_u32(indices)
+}
+
+/// Converts 32-bit indices produced by native code back to the caller's index type.
+///
+/// Only for native output that can't exceed the input indices, e.g. reordered triangles; this
+/// is checked in debug builds.
+pub(crate) fn indices_from_u32<I: Index>(indices: Vec<u32>) -> Vec<I> {
+    I::vec_from_u32(indices)
+}
+
+/// Converts 32-bit indices produced by native code back to the caller's index type, failing on
+/// the first index that doesn't fit.
+pub(crate) fn try_indices_from_u32<I: Index>(indices: Vec<u32>) -> Result<Vec<I>> {
+    I::try_vec_from_u32(indices)
+}
+
+/// Runs `f` on `indices` as 32-bit indices, writing the result back if they are 16-bit.
+///
+/// Only for native code that can't exceed the input indices; this is checked in debug builds.
+pub(crate) fn with_indices_u32_mut<I: Index, R>(
+    indices: &mut [I],
+    f: impl FnOnce(&mut [u32]) -> R,
+) -> R {
+    I::with_u32_mut(indices, f)
 }
 
 /// Quantize a float in [0..1] range into an N-bit fixed point unorm value.
@@ -193,6 +436,32 @@ impl<'a> VertexDataAdapter<'a> {
         let positions = unsafe { vertex_data.add(self.position_offset) };
         positions.cast()
     }
+
+    /// Reads the positions of all vertices.
+    pub(crate) fn positions(&self) -> Vec<[f32; 3]> {
+        let vertex_data = self.reader.get_ref();
+        (0..self.vertex_count)
+            .map(|vertex| {
+                let offset = vertex * self.vertex_stride + self.position_offset;
+                let mut position = [0f32; 3];
+                for (i, component) in position.iter_mut().enumerate() {
+                    let bytes = &vertex_data[offset + i * 4..offset + i * 4 + 4];
+                    *component = f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+                position
+            })
+            .collect()
+    }
+}
+
+/// Creates an adapter for tightly packed positions.
+pub(crate) fn positions_adapter(positions: &[[f32; 3]]) -> VertexDataAdapter<'_> {
+    VertexDataAdapter {
+        reader: Cursor::new(typed_to_bytes(positions)),
+        vertex_count: positions.len(),
+        vertex_stride: std::mem::size_of::<f32>() * 3,
+        position_offset: 0,
+    }
 }
 
 impl<'a> Read for VertexDataAdapter<'a> {